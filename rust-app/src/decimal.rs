@@ -0,0 +1,288 @@
+use core::convert::TryFrom;
+use core::fmt;
+
+// A non-negative fixed-point decimal, the value being mantissa * 10^-scale. The mantissa is kept
+// without trailing zeros in the fractional part, so that equal values have the same
+// representation.
+//
+// This is used to validate the amounts and gas parameters before they are hashed, and to show
+// them to the user; the text which is hashed is always the one which was validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decimal {
+    mantissa: u128,
+    scale: u32,
+}
+
+// 10^38 is the largest power of ten which fits in a u128
+const MAX_SCALE: u32 = 38;
+
+// The coin contract enforces amounts to have at most 12 decimal places
+pub const KDA_PRECISION: u32 = 12;
+
+impl Decimal {
+    pub const fn from_integer(v: u128) -> Decimal {
+        Decimal {
+            mantissa: v,
+            scale: 0,
+        }
+    }
+
+    fn new(mantissa: u128, scale: u32) -> Option<Decimal> {
+        let mut d = Decimal { mantissa, scale };
+        while d.scale > 0 && d.mantissa % 10 == 0 {
+            d.mantissa /= 10;
+            d.scale -= 1;
+        }
+        if d.scale > MAX_SCALE {
+            return None;
+        }
+        Some(d)
+    }
+
+    // Parse a JSON number, ie "600", "0.00001", or "1.0e-5". Leading zeros are allowed, but the
+    // integer part and the fractional part (if there is a '.') must have digits, so "1." and ".5"
+    // are rejected.
+    pub fn parse(s: &str) -> Option<Decimal> {
+        let (number, exponent) = match s.split_once(|c| c == 'e' || c == 'E') {
+            Some((n, e)) => (n, Some(e)),
+            None => (s, None),
+        };
+        let (int, frac) = match number.split_once('.') {
+            Some((_, "")) => return None,
+            Some((int, frac)) => (int, frac),
+            None => (number, ""),
+        };
+        if int.is_empty() {
+            return None;
+        }
+        let mut mantissa: u128 = 0;
+        for c in int.bytes().chain(frac.bytes()) {
+            if !c.is_ascii_digit() {
+                return None;
+            }
+            mantissa = mantissa.checked_mul(10)?.checked_add((c - b'0') as u128)?;
+        }
+        let mut scale = frac.len() as i64;
+        if let Some(e) = exponent {
+            let (negative, digits) = match e.as_bytes() {
+                [b'-', ..] => (true, &e[1..]),
+                [b'+', ..] => (false, &e[1..]),
+                _ => (false, e),
+            };
+            if digits.is_empty() || digits.len() > 3 || !digits.bytes().all(|c| c.is_ascii_digit())
+            {
+                return None;
+            }
+            let e: i64 = digits.parse().ok()?;
+            scale += if negative { e } else { -e };
+        }
+        while scale < 0 {
+            mantissa = mantissa.checked_mul(10)?;
+            scale += 1;
+        }
+        Decimal::new(mantissa, u32::try_from(scale).ok()?)
+    }
+
+    // Parse a Pact decimal literal, ie a JSON number without an exponent
+    pub fn parse_plain(s: &str) -> Option<Decimal> {
+        if s.contains(|c| c == 'e' || c == 'E') {
+            return None;
+        }
+        Decimal::parse(s)
+    }
+
+    // Parse a gas price as the app writes it into the transactions it builds: a Pact decimal
+    // literal, or a decimal with a negative exponent as Pact shows small values, eg "1.0e-5". Other
+    // forms, such as "1E+5" or "1e-5", are rejected as the text is hashed verbatim.
+    pub fn parse_gas_price(s: &str) -> Option<Decimal> {
+        match s.split_once('e') {
+            Some((number, exponent)) if number.contains('.') && exponent.starts_with('-') => {
+                Decimal::parse(s)
+            }
+            Some(_) => None,
+            None => Decimal::parse_plain(s),
+        }
+    }
+
+    pub fn with_max_precision(self, precision: u32) -> Option<Decimal> {
+        if self.scale > precision {
+            return None;
+        }
+        Some(self)
+    }
+
+    pub fn checked_add(&self, other: &Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        let align = |d: &Decimal| d.mantissa.checked_mul(10u128.checked_pow(scale - d.scale)?);
+        Decimal::new(align(self)?.checked_add(align(other)?)?, scale)
+    }
+
+    pub fn checked_mul(&self, other: &Decimal) -> Option<Decimal> {
+        Decimal::new(
+            self.mantissa.checked_mul(other.mantissa)?,
+            self.scale + other.scale,
+        )
+    }
+
+    fn integer_part(&self) -> u128 {
+        self.mantissa / 10u128.pow(self.scale)
+    }
+
+    fn fractional_part(&self) -> u128 {
+        self.mantissa % 10u128.pow(self.scale)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> core::cmp::Ordering {
+        // Compare the integer parts, then the fractional parts scaled to the same number of digits
+        let scale = self.scale.max(other.scale);
+        let frac = |d: &Decimal| d.fractional_part() * 10u128.pow(scale - d.scale);
+        (self.integer_part(), frac(self)).cmp(&(other.integer_part(), frac(other)))
+    }
+}
+
+// Shown in normal form with thousands separators, eg "1,234,567.0123"
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = [0u8; 39];
+        let mut len = 0;
+        let mut int = self.integer_part();
+        loop {
+            digits[len] = b'0' + (int % 10) as u8;
+            len += 1;
+            int /= 10;
+            if int == 0 {
+                break;
+            }
+        }
+        for (i, d) in digits[..len].iter().enumerate().rev() {
+            write!(f, "{}", *d as char)?;
+            if i > 0 && i % 3 == 0 {
+                write!(f, ",")?;
+            }
+        }
+        if self.scale > 0 {
+            write!(
+                f,
+                ".{:0width$}",
+                self.fractional_part(),
+                width = self.scale as usize
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(target_family = "bolos")))]
+mod tests {
+    use super::*;
+    use arrayvec::ArrayString;
+    use core::fmt::Write;
+
+    fn display(d: Decimal) -> ArrayString<64> {
+        let mut s = ArrayString::new();
+        write!(s, "{}", d).unwrap();
+        s
+    }
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::parse(s).unwrap()
+    }
+
+    #[test]
+    fn gas_price() {
+        assert_eq!(
+            Decimal::parse_gas_price("1.0e-5"),
+            Decimal::parse("0.00001")
+        );
+        assert_eq!(
+            Decimal::parse_gas_price("0.00001"),
+            Decimal::parse("0.00001")
+        );
+        assert_eq!(
+            Decimal::parse_gas_price("2"),
+            Some(Decimal::from_integer(2))
+        );
+        for price in ["1E+5", "1e5", "1e-5", "1.0E-5", "1.0e+5", "1.0e-", "1.", ""] {
+            assert_eq!(Decimal::parse_gas_price(price), None, "{}", price);
+        }
+    }
+
+    #[test]
+    fn precision() {
+        let amount = Decimal::parse_plain("1.000000000001000").unwrap();
+        assert_eq!(amount.with_max_precision(KDA_PRECISION), Some(amount));
+        let amount = Decimal::parse_plain("1.0000000000001").unwrap();
+        assert_eq!(amount.with_max_precision(KDA_PRECISION), None);
+        assert_eq!(dec("1.5").with_max_precision(0), None);
+        assert_eq!(dec("1.5").with_max_precision(1), Some(dec("1.5")));
+        assert_eq!(
+            dec("2.500000000000000000").with_max_precision(1),
+            Some(dec("2.5"))
+        );
+        assert_eq!(
+            Decimal::from_integer(7).with_max_precision(0),
+            Some(Decimal::from_integer(7))
+        );
+    }
+
+    #[test]
+    fn normalisation() {
+        assert_eq!(dec("007.50"), dec("7.5"));
+        assert_eq!(dec("0.000"), Decimal::from_integer(0));
+        assert_eq!(dec("1.0e2"), Decimal::from_integer(100));
+        assert_eq!(dec("1.0e-5"), dec("0.00001"));
+        assert_eq!(display(dec("00012.3400")).as_str(), "12.34");
+    }
+
+    #[test]
+    fn display_separators() {
+        assert_eq!(display(dec("0")).as_str(), "0");
+        assert_eq!(display(dec("0.5")).as_str(), "0.5");
+        assert_eq!(display(dec("999")).as_str(), "999");
+        assert_eq!(display(dec("1000")).as_str(), "1,000");
+        assert_eq!(display(dec("1234567.0123")).as_str(), "1,234,567.0123");
+        assert_eq!(display(dec("1.0e-5")).as_str(), "0.00001");
+        assert_eq!(
+            display(Decimal::from_integer(u128::MAX)).as_str(),
+            "340,282,366,920,938,463,463,374,607,431,768,211,455"
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(dec("1.5") > dec("1.05"));
+        assert!(dec("2") > dec("1.999999"));
+        assert!(dec("0.1") < dec("1"));
+        assert_eq!(dec("1.10").cmp(&dec("1.1")), core::cmp::Ordering::Equal);
+        assert_eq!(dec("3").max(dec("10.5")), dec("10.5"));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(dec("0.1").checked_add(&dec("0.2")), Some(dec("0.3")));
+        assert_eq!(
+            dec("1.5").checked_add(&dec("2.5")),
+            Some(Decimal::from_integer(4))
+        );
+        assert_eq!(
+            dec("1.5").checked_mul(&Decimal::from_integer(2)),
+            Some(Decimal::from_integer(3))
+        );
+        assert_eq!(dec("2300").checked_mul(&dec("0.00001")), Some(dec("0.023")));
+
+        // Overflows of the u128 mantissa, or of the largest scale
+        let max = Decimal::from_integer(u128::MAX);
+        assert_eq!(max.checked_add(&Decimal::from_integer(1)), None);
+        assert_eq!(max.checked_add(&dec("0.1")), None);
+        assert_eq!(max.checked_mul(&Decimal::from_integer(2)), None);
+        assert_eq!(dec("1e-20").checked_mul(&dec("1e-20")), None);
+    }
+}
@@ -1,5 +1,8 @@
 #![allow(clippy::type_complexity)]
+use crate::decimal::*;
 use crate::interface::*;
+use crate::pact::*;
+use crate::settings::*;
 use crate::utils::*;
 use crate::*;
 use arrayvec::ArrayString;
@@ -21,7 +24,6 @@ use ledger_parser_combinators::json::Json;
 use ledger_prompts_ui::{final_accept_prompt, mk_prompt_write, ScrollerError};
 
 use core::convert::TryFrom;
-use core::ops::Deref;
 use core::str::from_utf8;
 use ledger_parser_combinators::define_json_struct_interp;
 use ledger_parser_combinators::interp_parser::*;
@@ -33,15 +35,189 @@ use ledger_device_sdk::ecc::{ECPrivateKey, Ed25519, SeedDerive};
 
 #[allow(clippy::upper_case_acronyms)]
 type PKH = Ed25519RawPubKeyAddress;
-const fn mkfnc<A, B, C>(q: fn(&A, &mut B, C) -> Option<()>) -> fn(&A, &mut B, C) -> Option<()> {
-    q
+
+// Decodes the escape sequences in the contents of a JSON string, giving None for an escape which
+// is invalid or cut off
+struct JsonUnescape<'a>(core::str::Chars<'a>);
+
+impl JsonUnescape<'_> {
+    fn hex4(&mut self) -> Option<u32> {
+        let mut v = 0;
+        for _ in 0..4 {
+            v = v * 16 + self.0.next()?.to_digit(16)?;
+        }
+        Some(v)
+    }
+
+    fn escape(&mut self) -> Option<char> {
+        Some(match self.0.next()? {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let high = self.hex4()?;
+                if (0xd800..0xdc00).contains(&high) {
+                    if self.0.next()? != '\\' || self.0.next()? != 'u' {
+                        return None;
+                    }
+                    let low = self.hex4()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return None;
+                    }
+                    char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))?
+                } else {
+                    // A lone low surrogate is not a char
+                    char::from_u32(high)?
+                }
+            }
+            _ => return None,
+        })
+    }
+}
+
+impl Iterator for JsonUnescape<'_> {
+    type Item = Option<char>;
+    fn next(&mut self) -> Option<Option<char>> {
+        match self.0.next()? {
+            '\\' => Some(self.escape()),
+            c => Some(Some(c)),
+        }
+    }
+}
+
+// Whitespace is shown as a single space. Any other control character, or a character outside
+// ASCII which the device cannot show, would hide part of what is signed.
+fn shown_char(c: char) -> Option<char> {
+    match c {
+        '\n' | '\r' | '\t' | '\u{c}' => Some(' '),
+        ' '..='~' => Some(c),
+        _ => None,
+    }
+}
+
+// Whether the contents of a JSON string can be shown as they are signed
+fn can_show_unescaped(s: &[u8]) -> bool {
+    from_utf8(s).map_or(false, |s| {
+        JsonUnescape(s.chars()).all(|c| c.and_then(shown_char).is_some())
+    })
+}
+
+// Write the contents of a JSON string with the escape sequences decoded; callers check that they
+// can be shown first.
+fn write_unescaped<W: Write>(w: &mut W, s: &[u8]) -> Result<(), ScrollerError> {
+    for c in JsonUnescape(from_utf8(s)?.chars()) {
+        w.write_char(c.and_then(shown_char).ok_or(ScrollerError)?)?;
+    }
+    Ok(())
+}
+
+// Write the start of the contents of a JSON string which is not shown in full, up to the first
+// character which cannot be shown or was cut off
+fn write_unescaped_start<W: Write>(w: &mut W, s: &[u8]) -> Result<(), ScrollerError> {
+    let s = from_utf8(s).or_else(|e| from_utf8(&s[..e.valid_up_to()]))?;
+    for c in JsonUnescape(s.chars()).map_while(|c| c.and_then(shown_char)) {
+        w.write_char(c)?;
+    }
+    Ok(())
+}
+
+// Parse a JSON number of whole seconds, allowing a zero fractional part as in "900.0"
+fn parse_seconds(s: &[u8]) -> Option<u64> {
+    let s = from_utf8(s).ok()?;
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    check_positive_integer(int)?;
+    if !frac.bytes().all(|c| c == b'0') {
+        return None;
+    }
+    int.parse().ok()
+}
+
+// Write a duration like "8h", "15m" or "1h 30m 5s"
+fn write_duration<W: Write>(w: &mut W, seconds: u64) -> Result<(), ScrollerError> {
+    let (h, m, s) = (seconds / 3600, (seconds / 60) % 60, seconds % 60);
+    let mut sep = "";
+    for (v, unit) in [(h, "h"), (m, "m"), (s, "s")] {
+        if v != 0 {
+            write!(w, "{}{}{}", sep, v, unit)?;
+            sep = " ";
+        }
+    }
+    if seconds == 0 {
+        write!(w, "0s")?;
+    }
+    Ok(())
+}
+
+// Write the Unix time as a UTC date, converting the days to the civil calendar as in
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn write_utc_date<W: Write>(w: &mut W, unix_seconds: u64) -> Result<(), ScrollerError> {
+    let z = unix_seconds / 86400 + 719468;
+    let secs = unix_seconds % 86400;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    write!(
+        w,
+        "{}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )?;
+    Ok(())
+}
+
+fn expert_mode() -> bool {
+    Settings.expert_mode()
+}
+
+// Write an account name, abbreviating the key of k: accounts to its first and last 8 digits
+// unless in expert mode
+fn write_account<W: Write>(w: &mut W, account: &[u8]) -> Result<(), ScrollerError> {
+    let account = from_utf8(account)?;
+    match account.strip_prefix("k:") {
+        Some(key)
+            if key.len() == 64 && key.bytes().all(|c| c.is_ascii_hexdigit()) && !expert_mode() =>
+        {
+            write!(w, "k:{}...{}", &key[..8], &key[56..])?
+        }
+        _ => write!(w, "{}", account)?,
+    }
+    Ok(())
+}
+
+// The response to an APDU, or the reason for rejecting it. OOB::Reject carries no reason, so a
+// parser which knows why it rejects finishes with the reason instead, and the status word is
+// answered without parsing the rest of the input.
+pub type ApduResult<const N: usize> = Result<ArrayVec<u8, N>, KadenaError>;
+
+fn reject<T>(destination: &mut Option<Result<T, KadenaError>>, reason: KadenaError) -> Option<()> {
+    *destination = Some(Err(reason));
+    Some(())
 }
 
-fn mkstr(v: Option<&[u8]>) -> Result<&str, ScrollerError> {
-    Ok(from_utf8(v.ok_or(ScrollerError)?)?)
+// Check that a path is under 44'/626'
+fn check_path(path: ArrayVec<u32, 10>) -> Result<ArrayVec<u32, 10>, KadenaError> {
+    if path.starts_with(&BIP32_PREFIX[0..2]) {
+        Ok(path)
+    } else {
+        Err(KadenaError::InvalidPath)
+    }
 }
 
-pub type GetAddressImplT = impl InterpParser<Bip32Key, Returning = ArrayVec<u8, 128_usize>>;
+pub type GetAddressImplT = impl InterpParser<Bip32Key, Returning = ApduResult<128>>;
 
 // Need a path of length 5, as make_bip32_path panics with smaller paths
 pub const BIP32_PREFIX: [u32; 5] =
@@ -51,25 +227,25 @@ pub const fn get_address_impl<const PROMPT: bool>() -> GetAddressImplT {
     Action(
         SubInterp(DefaultInterp),
         mkfn(
-            |path: &ArrayVec<u32, 10>, destination: &mut Option<ArrayVec<u8, 128>>| -> Option<()> {
+            |path: &ArrayVec<u32, 10>, destination: &mut Option<ApduResult<128>>| -> Option<()> {
                 if !path.starts_with(&BIP32_PREFIX[0..2]) {
-                    return None;
+                    return reject(destination, KadenaError::InvalidPath);
                 }
                 with_public_keys(path, false, |key: &_, pkh: &PKH| {
                     try_option(|| -> Option<()> {
                         if PROMPT {
                             scroller("Provide Public Key", |_w| Ok(()))?;
                             scroller_paginated("Address", |w| Ok(write!(w, "k:{pkh}")?))?;
-                            final_accept_prompt(&[])?;
+                            if final_accept_prompt(&[]).is_none() {
+                                return reject(destination, KadenaError::UserRejected);
+                            }
                         }
-                        *destination = Some(ArrayVec::new());
+                        let mut rv = ArrayVec::new();
                         // key without y parity
                         let key_x = ed25519_public_key_bytes(key);
-                        destination
-                            .as_mut()?
-                            .try_push(u8::try_from(key_x.len()).ok()?)
-                            .ok()?;
-                        destination.as_mut()?.try_extend_from_slice(key_x).ok()?;
+                        rv.try_push(u8::try_from(key_x.len()).ok()?).ok()?;
+                        rv.try_extend_from_slice(key_x).ok()?;
+                        *destination = Some(Ok(rv));
                         Some(())
                     }())
                 })
@@ -79,7 +255,7 @@ pub const fn get_address_impl<const PROMPT: bool>() -> GetAddressImplT {
     )
 }
 
-pub type SignImplT = impl InterpParser<SignParameters, Returning = ArrayVec<u8, 128_usize>>;
+pub type SignImplT = impl InterpParser<SignParameters, Returning = ApduResult<128>>;
 
 #[derive(PartialEq, Debug)]
 enum CapabilityCoverage {
@@ -105,392 +281,2381 @@ impl Summable<CapabilityCoverage> for CapabilityCoverage {
     }
 }
 
-pub static SIGN_IMPL: SignImplT = Action(
-    (
-        Action(
-            // Calculate the hash of the transaction
-            ObserveLengthedBytes(
-                Hasher::new,
-                Hasher::update,
-                Json(Action(Preaction( || -> Option<()> { scroller("Signing", |w| Ok(write!(w, "Transaction")?)) } , KadenaCmdInterp {
-                    field_nonce: DropInterp,
-                    field_meta: META_ACTION,
-                    field_payload: PayloadInterp {
-                        field_exec: CommandInterp {
-                            field_code: DropInterp,
-                            field_data: DropInterp
-                        }},
-                    field_signers: SubInterpM::<_, CapabilityCoverage>::new(Action(Preaction(
-                            || -> Option<()> {
-                                scroller("Requiring", |w| Ok(write!(w, "Capabilities")?))
-                            },
-                            SignerInterp {
-                        field_scheme: DropInterp,
-                        field_pub_key: MoveAction(JsonStringAccumulate::<64>, mkmvfn(|key : ArrayVec<u8, 64>, dest: &mut Option<ArrayVec<u8, 64>>| -> Option<()> {
-                            scroller("Of Key", |w| Ok(write!(w, "{}", from_utf8(key.as_slice())?)?))?;
-                            set_from_thunk(dest, || Some(key));
-                            Some(())
-                        })),
-                        field_addr: DropInterp,
-                        field_clist: Alt(DropInterp, CLIST_ACTION),
-                    }),
-                        mkfn(|signer: &Signer<_,Option<ArrayVec<u8, 64>>,_, Option<AltResult<(),(CapCountData, All)>>>, dest: &mut Option<CapabilityCoverage> | {
-                            *dest = Some(match signer.field_clist {
-                                Some(AltResult::Second((CapCountData::CapCount{total_caps,..}, All(a)))) if total_caps > 0 => if a {CapabilityCoverage::Full} else {CapabilityCoverage::HasFallback},
-                                _ => {
-                                    match from_utf8(signer.field_pub_key.as_ref()?.as_slice()) {
-                                        Ok(pub_key) => scroller("Unscoped Signer", |w| Ok(write!(w, "{}", pub_key)?)),
-                                        _ => Some(()),
-                                    };
-                                    CapabilityCoverage::NoCaps
-                                },
-                            });
-                            Some(())
-                        })),
-                        ),
-                    field_network_id: Action(Alt(JsonStringAccumulate::<32>, DropInterp), mkvfn(|mnet: &AltResult<ArrayVec<u8, 32>, ()>, dest: &mut Option<()>| {
-                        *dest = Some(());
-                        match mnet {
-                            AltResult::First(net) => {
-                                scroller("On Network", |w| Ok(write!(w, "{}", from_utf8(net.as_slice())?)?))
-                            }
-                            _ => { Some(())} // Ignore null
-                        }
-                    }))
-                }),
-                mkvfn(|cmd : &KadenaCmd<_,_,Option<CapabilityCoverage>,_,_>, _| {
-                    match cmd.field_signers.as_ref() {
-                        Some(CapabilityCoverage::Full) => { }
-                        Some(CapabilityCoverage::HasFallback) => {
-                            scroller("WARNING", |w| Ok(write!(w, "Transaction too large for Ledger to display.  PROCEED WITH GREAT CAUTION.  Do you want to continue?")?))?;
-                        }
-                        _ => {
-                            scroller("WARNING", |w| Ok(write!(w, "UNSAFE TRANSACTION. This transaction's code was not recognized and does not limit capabilities for all signers. Signing this transaction may make arbitrary actions on the chain including loss of all funds.")?))?;
-                        }
-                    }
-                    Some(())
-                })
-                )),
-            true),
-            // Ask the user if they accept the transaction body's hash
-            mkfn(|(_, mut hasher): &(_, Blake2b), destination: &mut Option<Zeroizing<Base64Hash<32>>>| {
-                let the_hash: Zeroizing<Base64Hash<32>> = hasher.finalize();
-                scroller("Transaction hash", |w| Ok(write!(w, "{}", the_hash.deref())?))?;
-                *destination=Some(the_hash);
-                Some(())
-            }),
-        ),
-        MoveAction(
-            SubInterp(DefaultInterp),
-            // And ask the user if this is the key the meant to sign with:
-            mkmvfn(|path: ArrayVec<u32, 10>, destination: &mut Option<ArrayVec<u32, 10>>| {
-                if !path.starts_with(&BIP32_PREFIX[0..2]) {
-                    return None;
-                }
-                with_public_keys(&path, false, |_, pkh: &PKH| { try_option(|| -> Option<()> {
-                    scroller("Sign for Address", |w| Ok(write!(w, "{pkh}")?))?;
-                    Some(())
-                }())}).ok()?;
-                *destination = Some(path);
-                Some(())
-            }),
-        ),
-    ),
-    mkfn(|(hash, path): &(Option<Zeroizing<Base64Hash<32>>>, Option<ArrayVec<u32, 10>>), destination: &mut _| {
-        #[allow(clippy::needless_borrow)] // Needed for nanos
-        final_accept_prompt(&[&"Sign Transaction?"])?;
-
-        // By the time we get here, we've approved and just need to do the signature.
-        let sig = eddsa_sign(path.as_ref()?, false, &hash.as_ref()?.0[..]).ok()?;
-        let mut rv = ArrayVec::<u8, 128>::new();
-        rv.try_extend_from_slice(&sig.0[..]).ok()?;
-        *destination = Some(rv);
-        Some(())
-    }),
-);
+// The transfer capabilities of all the signers; a single transfer can be cross checked against
+// the transfer done by the code.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum TransferCaps {
+    NoTransfer,
+    Single(TransferSummary),
+    Unmatchable,
+}
 
-const META_ACTION: Action<
-    Alt<
-        MetaInterp<
-            Action<
-                JsonStringAccumulate<32_usize>,
-                fn(&ArrayVec<u8, 32_usize>, &mut Option<()>) -> Option<()>,
-            >,
-            DropInterp,
-            JsonStringAccumulate<100_usize>,
-            JsonStringAccumulate<100_usize>,
-            DropInterp,
-            DropInterp,
-        >,
-        DropInterp,
-    >,
-    fn(
-        &AltResult<
-            Meta<
-                Option<()>,
-                Option<()>,
-                Option<ArrayVec<u8, 100_usize>>,
-                Option<ArrayVec<u8, 100_usize>>,
-                Option<()>,
-                Option<()>,
-            >,
-            (),
-        >,
-        &mut Option<()>,
-    ) -> Option<()>,
-> = Action(
-    Alt(
-        MetaInterp {
-            field_chain_id: Action(
-                JsonStringAccumulate::<32>,
-                mkvfn(|chain: &ArrayVec<u8, 32>, _| -> Option<()> {
-                    scroller("On Chain", |w| {
-                        Ok(write!(w, "{}", from_utf8(chain.as_slice())?)?)
-                    })
-                }),
-            ),
-            field_sender: DropInterp,
-            field_gas_limit: JsonStringAccumulate::<100>,
-            field_gas_price: JsonStringAccumulate::<100>,
-            field_ttl: DropInterp,
-            field_creation_time: DropInterp,
-        },
-        DropInterp,
-    ),
-    mkvfn(|v, _| match v {
-        AltResult::First(Meta {
-            ref field_gas_limit,
-            ref field_gas_price,
-            ..
-        }) => scroller("Using Gas", |w| {
-            Ok(write!(
-                w,
-                "at most {} at price {}",
-                from_utf8(field_gas_limit.as_ref().ok_or(ScrollerError)?.as_slice())?,
-                from_utf8(field_gas_price.as_ref().ok_or(ScrollerError)?.as_slice())?
-            )?)
-        }),
-        _ => scroller("CAUTION", |w| {
-            Ok(write!(w, "'meta' field of transaction not recognized")?)
-        }),
-    }),
-);
+impl Summable<TransferCaps> for TransferCaps {
+    fn zero() -> Self {
+        TransferCaps::NoTransfer
+    }
+    fn add_and_set(&mut self, other: &TransferCaps) {
+        match (*self, other) {
+            (_, TransferCaps::NoTransfer) => {}
+            (TransferCaps::NoTransfer, _) => *self = *other,
+            // Co-signers scoping the same transfer grant a single transfer
+            (TransferCaps::Single(a), TransferCaps::Single(b)) if a == *b => {}
+            _ => *self = TransferCaps::Unmatchable,
+        }
+    }
+}
 
-#[derive(Debug, Clone, Copy)]
-enum CapCountData {
-    IsTransfer,
-    IsUnknownCap,
-    CapCount {
-        total_caps: u16,
-        total_transfers: u16,
-        total_unknown: u16,
-    },
+// The amounts of the transfer capabilities, summed for each token and sender, so that the user
+// does not have to add up several transfers.
+#[cfg(target_os = "nanos")]
+const MAX_TRANSFER_TOTALS: usize = 2;
+#[cfg(not(target_os = "nanos"))]
+const MAX_TRANSFER_TOTALS: usize = 8;
+
+#[derive(Debug, Clone)]
+struct TransferTotal {
+    module: ArrayString<TRANSFER_MODULE_SIZE>,
+    // As in the JSON string; a k: account fits
+    sender: ArrayString<66>,
+    amount: Decimal,
 }
 
-impl Summable<CapCountData> for CapCountData {
-    fn add_and_set(&mut self, other: &CapCountData) {
-        if let CapCountData::CapCount {
-            total_caps,
-            total_transfers,
-            total_unknown,
-        } = self
-        {
-            let add1 = |c: &mut u16| match c.checked_add(1) {
-                Some(v) => *c = v,
-                None => panic!("u16 overflow"),
+// The transfers summed, by a hash of their arguments, with the index of their total; the same
+// capability scoped by several signers is one transfer, and is only summed once.
+#[cfg(target_os = "nanos")]
+const MAX_SUMMED_TRANSFERS: usize = 4;
+#[cfg(not(target_os = "nanos"))]
+const MAX_SUMMED_TRANSFERS: usize = 16;
+
+#[derive(Debug, Clone)]
+struct SummedTransfer {
+    hash: [u8; 16],
+    total: usize,
+    amount: Decimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferTotals {
+    totals: ArrayVec<TransferTotal, MAX_TRANSFER_TOTALS>,
+    transfers: ArrayVec<SummedTransfer, MAX_SUMMED_TRANSFERS>,
+    // The number of distinct transfers
+    count: usize,
+    // Whether all the transfers could be summed, ie the amounts are valid, there are not too many
+    // senders and tokens, and the sums do not overflow
+    complete: bool,
+}
+
+impl TransferTotals {
+    // Add the amount of a transfer capability, in place, unless the same transfer was added before
+    fn add_transfer(
+        &mut self,
+        module: &str,
+        sender: &str,
+        receiver: &str,
+        amount: &str,
+        target_chain: Option<&str>,
+    ) {
+        match (|| {
+            let amount = parse_cap_amount(amount)?;
+            let hash = transfer_hash(module, sender, receiver, &amount, target_chain)?;
+            let total = TransferTotal {
+                module: ArrayString::from(module).ok()?,
+                sender: ArrayString::from(json_string(sender)?).ok()?,
+                amount,
             };
-            add1(total_caps);
-            match other {
-                CapCountData::IsTransfer => add1(total_transfers),
-                CapCountData::IsUnknownCap => add1(total_unknown),
-                _ => {}
+            Some((hash, total))
+        })() {
+            Some((hash, total)) => self.add_summed(hash, &total),
+            None => {
+                self.count += 1;
+                self.complete = false;
+            }
+        }
+    }
+
+    // Add the amount of a transfer to the total of its token and sender
+    fn add_summed(&mut self, hash: [u8; 16], transfer: &TransferTotal) {
+        if self.transfers.iter().any(|t| t.hash == hash) {
+            return;
+        }
+        self.count += 1;
+        let total = match self
+            .totals
+            .iter()
+            .position(|t| t.module == transfer.module && t.sender == transfer.sender)
+        {
+            Some(i) => match self.totals[i].amount.checked_add(&transfer.amount) {
+                Some(amount) => {
+                    self.totals[i].amount = amount;
+                    i
+                }
+                None => {
+                    self.complete = false;
+                    return;
+                }
+            },
+            None => {
+                if self.totals.try_push(transfer.clone()).is_err() {
+                    self.complete = false;
+                    return;
+                }
+                self.totals.len() - 1
             }
+        };
+        let summed = SummedTransfer {
+            hash,
+            total,
+            amount: transfer.amount,
+        };
+        if self.transfers.try_push(summed).is_err() {
+            self.complete = false;
         }
     }
+}
+
+// A hash of the arguments of a transfer capability, to tell the same transfer scoped by several
+// signers; the arguments are JSON, so they do not contain the newlines separating them.
+fn transfer_hash(
+    module: &str,
+    sender: &str,
+    receiver: &str,
+    amount: &Decimal,
+    target_chain: Option<&str>,
+) -> Option<[u8; 16]> {
+    let mut hasher: Blake2b = Hasher::new();
+    write!(hasher, "{}\n{}\n{}\n{}", module, sender, receiver, amount).ok()?;
+    if let Some(chain) = target_chain {
+        write!(hasher, "\n{}", chain).ok()?;
+    }
+    let hash: Base64Hash<32> = hasher.finalize();
+    let mut rv = [0; 16];
+    rv.copy_from_slice(&hash.0[..16]);
+    Some(rv)
+}
+
+impl Summable<TransferTotals> for TransferTotals {
     fn zero() -> Self {
-        CapCountData::CapCount {
-            total_caps: 0,
-            total_transfers: 0,
-            total_unknown: 0,
+        TransferTotals {
+            totals: ArrayVec::new(),
+            transfers: ArrayVec::new(),
+            count: 0,
+            complete: true,
+        }
+    }
+    fn add_and_set(&mut self, other: &TransferTotals) {
+        // The transfers which could not be summed are counted as distinct
+        self.count += other.count - other.transfers.len();
+        self.complete &= other.complete;
+        for transfer in other.transfers.iter() {
+            let total = &other.totals[transfer.total];
+            self.add_summed(
+                transfer.hash,
+                &TransferTotal {
+                    module: total.module,
+                    sender: total.sender,
+                    amount: transfer.amount,
+                },
+            );
         }
     }
 }
 
-const CLIST_ACTION: SubInterpMFold<
-    Action<
-        KadenaCapabilityInterp<KadenaCapabilityArgsInterp, JsonStringAccumulate<32>>,
-        fn(
-            &KadenaCapability<
-                Option<<KadenaCapabilityArgsInterp as ParserCommon<JsonArray<JsonAny>>>::Returning>,
-                Option<ArrayVec<u8, 32>>,
-            >,
-            &mut Option<(CapCountData, bool)>,
-            (CapCountData, All),
-        ) -> Option<()>,
-    >,
-    (CapCountData, All),
-> = SubInterpMFold::new(Action(
-    KadenaCapabilityInterp {
-        field_args: KadenaCapabilityArgsInterp,
-        field_name: JsonStringAccumulate::<32>,
-    },
-    mkfnc(
-        |cap: &KadenaCapability<
-            Option<<KadenaCapabilityArgsInterp as ParserCommon<JsonArray<JsonAny>>>::Returning>,
-            Option<ArrayVec<u8, 32>>,
-        >,
-         destination: &mut Option<(CapCountData, bool)>,
-         v: (CapCountData, All)| {
-            let name = cap.field_name.as_ref()?.as_slice();
-            let name_utf8 = from_utf8(name).ok()?;
-            let mk_unknown_cap_title = || -> Option<_> {
-                let count = match v.0 {
-                    CapCountData::CapCount { total_unknown, .. } => total_unknown,
-                    _ => 0,
-                };
-                let mut buffer: ArrayString<22> = ArrayString::new();
-                write!(
-                    mk_prompt_write(&mut buffer),
-                    "Unknown Capability {}",
-                    count + 1
-                )
-                .ok()?;
-                Some(buffer)
-            };
-            let mk_transfer_title = || -> Option<_> {
-                let count = match v.0 {
-                    CapCountData::CapCount {
-                        total_transfers, ..
-                    } => total_transfers,
-                    _ => 0,
-                };
-                let mut buffer: ArrayString<22> = ArrayString::new();
-                write!(mk_prompt_write(&mut buffer), "Transfer {}", count + 1).ok()?;
-                Some(buffer)
-            };
+// Show the totals before signing, whenever there are transfers
+fn show_transfer_totals(totals: &TransferTotals) -> Option<()> {
+    if totals.count == 0 {
+        return Some(());
+    }
+    if !totals.complete {
+        return scroller("Total Sending", |w| {
+            Ok(write!(w, "cannot be displayed on Ledger")?)
+        });
+    }
+    for total in totals.totals.iter() {
+        scroller("Total Sending", |w| {
+            write!(w, "{}", total.amount)?;
+            match total.module.as_str() {
+                "coin" => write!(w, " KDA")?,
+                module => write!(w, " {}", module)?,
+            }
+            if totals.totals.len() > 1 {
+                write!(w, " from ")?;
+                write_account(w, total.sender.as_bytes())?;
+            }
+            Ok(())
+        })?;
+    }
+    Some(())
+}
 
-            trace!("Prompting for capability");
-            *destination = Some((CapCountData::IsUnknownCap, true));
-            match cap.field_args.as_ref() {
-                Some((None, _)) => {
-                    if name == b"coin.GAS" {
-                        scroller("Paying Gas", |w| Ok(write!(w, " ")?))?;
-                        *destination = Some((Summable::zero(), true));
-                        trace!("Accepted gas");
-                    } else {
-                        scroller(&mk_unknown_cap_title()?, |w| {
-                            Ok(write!(w, "name: {}, no args", name_utf8)?)
-                        })?;
-                    }
+// The public keys of the signers, in order, to check that the key we sign with is one of them.
+// Keys which are not 64 hex digits are kept as None, as they cannot be ours.
+#[cfg(target_os = "nanos")]
+const MAX_TRACKED_SIGNERS: usize = 4;
+#[cfg(not(target_os = "nanos"))]
+const MAX_TRACKED_SIGNERS: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct SignerKeys {
+    keys: ArrayVec<Option<[u8; 32]>, MAX_TRACKED_SIGNERS>,
+    // The number of signers, which may be more than the number of tracked keys
+    count: usize,
+}
+
+impl SignerKeys {
+    fn single(key: Option<&[u8]>) -> SignerKeys {
+        let mut keys = ArrayVec::new();
+        keys.push(key.and_then(parse_pub_key));
+        SignerKeys { keys, count: 1 }
+    }
+
+    fn position(&self, key: &[u8]) -> Option<usize> {
+        self.keys
+            .iter()
+            .position(|k| k.as_ref().map(|k| &k[..]) == Some(key))
+    }
+}
+
+impl Summable<SignerKeys> for SignerKeys {
+    fn zero() -> Self {
+        SignerKeys {
+            keys: ArrayVec::new(),
+            count: 0,
+        }
+    }
+    fn add_and_set(&mut self, other: &SignerKeys) {
+        for k in other.keys.iter() {
+            if self.keys.try_push(*k).is_err() {
+                break;
+            }
+        }
+        self.count += other.count;
+    }
+}
+
+fn parse_pub_key(key: &[u8]) -> Option<[u8; 32]> {
+    if key.len() != 64 {
+        return None;
+    }
+    let digit = |c: u8| (c as char).to_digit(16);
+    let mut rv = [0; 32];
+    for (b, pair) in rv.iter_mut().zip(key.chunks(2)) {
+        *b = (digit(pair[0])? * 16 + digit(pair[1])?) as u8;
+    }
+    Some(rv)
+}
+
+// Everything we need to know about the signers once the transaction has been shown
+struct SignersSummary {
+    coverage: CapabilityCoverage,
+    transfer_caps: TransferCaps,
+    totals: TransferTotals,
+    keys: SignerKeys,
+    // The signers which were not shown, as they are not for our key
+    others: usize,
+    // The earliest timeout of the capabilities shown, as a Unix time
+    earliest_timeout: Option<u64>,
+}
+
+impl Summable<SignersSummary> for SignersSummary {
+    fn zero() -> Self {
+        SignersSummary {
+            coverage: Summable::zero(),
+            transfer_caps: Summable::zero(),
+            totals: Summable::zero(),
+            keys: Summable::zero(),
+            others: 0,
+            earliest_timeout: None,
+        }
+    }
+    fn add_and_set(&mut self, other: &SignersSummary) {
+        self.coverage.add_and_set(&other.coverage);
+        self.transfer_caps.add_and_set(&other.transfer_caps);
+        self.totals.add_and_set(&other.totals);
+        self.keys.add_and_set(&other.keys);
+        self.others += other.others;
+        self.earliest_timeout = earliest_timeout(self.earliest_timeout, other.earliest_timeout);
+    }
+}
+
+// Whether the signer being parsed is the one for the key we sign with, once its pubKey is known
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CurrentSigner {
+    Unknown,
+    Ours,
+    Other,
+}
+
+type SignerFieldInterpT = Alt<JsonStringAccumulate<8>, DropInterp>;
+type SignerKeyInterpT = Alt<JsonStringAccumulate<64>, DropInterp>;
+type SignerClistInterpT = Alt<DropInterp, ClistInterp>;
+type AltStringSchema = Alt<JsonString, JsonString>;
+type SignerClistSchema = Alt<JsonNull, JsonArray<KadenaCapabilitySchema>>;
+const SIGNER_FIELD_INTERP: SignerFieldInterpT = Alt(JsonStringAccumulate, DropInterp);
+const SIGNER_KEY_INTERP: SignerKeyInterpT = Alt(JsonStringAccumulate, DropInterp);
+const SIGNER_CLIST_INTERP: SignerClistInterpT = Alt(DropInterp, ClistInterp);
+
+// A signer of the transaction, summarised for the review. When the path is given before the
+// transaction, key is the key for the path: only the capabilities of its signer are shown, and the
+// other signers are counted; if the clist comes before the pubKey we cannot tell, and show it.
+// Without a key, every signer is shown.
+pub struct SignerSummaryInterp {
+    key: Option<[u8; 32]>,
+}
+
+pub enum SignerSummaryState {
+    Start,
+    Begin,
+    Name(<SignerFieldInterpT as ParserCommon<AltStringSchema>>::State),
+    NameSep,
+    PubKey(<SignerKeyInterpT as ParserCommon<AltStringSchema>>::State),
+    Clist(<SignerClistInterpT as ParserCommon<SignerClistSchema>>::State),
+    Drop(<DropInterp as ParserCommon<JsonAny>>::State),
+    ValueSep,
+}
+
+impl ParserCommon<SignerSchema> for SignerSummaryInterp {
+    type State = (
+        SignerSummaryState,
+        Option<<SignerFieldInterpT as ParserCommon<AltStringSchema>>::Returning>,
+        Option<<SignerKeyInterpT as ParserCommon<AltStringSchema>>::Returning>,
+        Option<<SignerClistInterpT as ParserCommon<SignerClistSchema>>::Returning>,
+        CurrentSigner,
+    );
+    type Returning = SignersSummary;
+    fn init(&self) -> Self::State {
+        (
+            SignerSummaryState::Start,
+            None,
+            None,
+            None,
+            CurrentSigner::Unknown,
+        )
+    }
+}
+
+// A pubKey which is too long to be accumulated is never ours, and cannot be shown
+fn write_signer_key<W: Write>(w: &mut W, key: Option<&[u8]>) -> Result<(), ScrollerError> {
+    match key {
+        Some(key) => write!(w, "{}", from_utf8(key)?)?,
+        None => write!(w, "cannot be displayed on Ledger")?,
+    }
+    Ok(())
+}
+
+impl SignerSummaryInterp {
+    // Show the pubKey, once it is known whether the signer is ours
+    fn show_pub_key(&self, key: Option<&[u8]>, current: &mut CurrentSigner) -> Option<()> {
+        let title = match self.key {
+            Some(ours) if key.and_then(parse_pub_key) != Some(ours) => {
+                *current = CurrentSigner::Other;
+                return Some(());
+            }
+            Some(_) => {
+                *current = CurrentSigner::Ours;
+                scroller("Requiring", |w| Ok(write!(w, "Capabilities")?))?;
+                "Of Your Key"
+            }
+            None => "Of Key",
+        };
+        scroller(title, |w| write_signer_key(w, key))
+    }
+
+    fn summary(
+        &self,
+        pub_key: Option<&[u8]>,
+        clist: Option<AltResult<(), ClistSummary>>,
+        current: CurrentSigner,
+    ) -> Option<SignersSummary> {
+        let keys = SignerKeys::single(pub_key);
+        let (coverage, transfer_caps, totals, others, earliest_timeout) = match clist {
+            Some(AltResult::Second(ClistSummary {
+                counts:
+                    CapCountData::CapCount {
+                        total_caps,
+                        transfer_caps,
+                        earliest_timeout,
+                        ..
+                    },
+                all,
+                totals,
+            })) if total_caps > 0 => (
+                if all {
+                    CapabilityCoverage::Full
+                } else {
+                    CapabilityCoverage::HasFallback
+                },
+                transfer_caps,
+                totals,
+                0,
+                earliest_timeout,
+            ),
+            // The signature of another signer does not grant anything for ours
+            _ if current == CurrentSigner::Other => (
+                CapabilityCoverage::Full,
+                TransferCaps::NoTransfer,
+                Summable::zero(),
+                1,
+                None,
+            ),
+            _ => {
+                scroller("Unscoped Signer", |w| write_signer_key(w, pub_key))?;
+                (
+                    CapabilityCoverage::NoCaps,
+                    TransferCaps::NoTransfer,
+                    Summable::zero(),
+                    0,
+                    None,
+                )
+            }
+        };
+        Some(SignersSummary {
+            coverage,
+            transfer_caps,
+            totals,
+            keys,
+            others,
+            earliest_timeout,
+        })
+    }
+}
+
+impl JsonInterp<SignerSchema> for SignerSummaryInterp {
+    #[inline(never)]
+    fn parse(
+        &self,
+        (state, name, pub_key, clist, current): &mut Self::State,
+        token: JsonToken<'_>,
+        destination: &mut Option<Self::Returning>,
+    ) -> Result<(), Option<OOB>> {
+        loop {
+            use SignerSummaryState::*;
+            match state {
+                Start if token == JsonToken::BeginObject => {
+                    if self.key.is_none() {
+                        scroller("Requiring", |w| Ok(write!(w, "Capabilities")?))
+                            .ok_or(Some(OOB::Reject))?;
+                    }
+                    set_from_thunk(state, || Begin);
+                }
+                Begin => {
+                    set_from_thunk(state, || {
+                        Name(<SignerFieldInterpT as ParserCommon<AltStringSchema>>::init(
+                            &SIGNER_FIELD_INTERP,
+                        ))
+                    });
+                    continue;
+                }
+                Name(ref mut s) => {
+                    <SignerFieldInterpT as JsonInterp<AltStringSchema>>::parse(
+                        &SIGNER_FIELD_INTERP,
+                        s,
+                        token,
+                        name,
+                    )?;
+                    set_from_thunk(state, || NameSep);
+                }
+                NameSep if token == JsonToken::NameSeparator => {
+                    let field = match name.take() {
+                        Some(AltResult::First(field)) => field,
+                        _ => ArrayVec::new(),
+                    };
+                    match field.as_slice() {
+                        b"pubKey" if pub_key.is_some() => return Err(Some(OOB::Reject)),
+                        b"clist" if clist.is_some() => return Err(Some(OOB::Reject)),
+                        b"pubKey" => set_from_thunk(state, || {
+                            PubKey(<SignerKeyInterpT as ParserCommon<AltStringSchema>>::init(
+                                &SIGNER_KEY_INTERP,
+                            ))
+                        }),
+                        b"clist" if *current != CurrentSigner::Other => {
+                            set_from_thunk(state, || {
+                                Clist(
+                                    <SignerClistInterpT as ParserCommon<SignerClistSchema>>::init(
+                                        &SIGNER_CLIST_INTERP,
+                                    ),
+                                )
+                            })
+                        }
+                        _ => set_from_thunk(state, || {
+                            Drop(<DropInterp as ParserCommon<JsonAny>>::init(&DropInterp))
+                        }),
+                    }
+                }
+                PubKey(ref mut s) => {
+                    <SignerKeyInterpT as JsonInterp<AltStringSchema>>::parse(
+                        &SIGNER_KEY_INTERP,
+                        s,
+                        token,
+                        pub_key,
+                    )?;
+                    let key = match pub_key.as_ref() {
+                        Some(AltResult::First(key)) => Some(key.as_slice()),
+                        _ => None,
+                    };
+                    self.show_pub_key(key, current).ok_or(Some(OOB::Reject))?;
+                    set_from_thunk(state, || ValueSep);
+                }
+                Clist(ref mut s) => {
+                    <SignerClistInterpT as JsonInterp<SignerClistSchema>>::parse(
+                        &SIGNER_CLIST_INTERP,
+                        s,
+                        token,
+                        clist,
+                    )?;
+                    set_from_thunk(state, || ValueSep);
+                }
+                Drop(ref mut s) => {
+                    <DropInterp as JsonInterp<JsonAny>>::parse(&DropInterp, s, token, &mut None)?;
+                    set_from_thunk(state, || ValueSep);
+                }
+                ValueSep if token == JsonToken::ValueSeparator => {
+                    set_from_thunk(state, || {
+                        Name(<SignerFieldInterpT as ParserCommon<AltStringSchema>>::init(
+                            &SIGNER_FIELD_INTERP,
+                        ))
+                    });
+                }
+                ValueSep if token == JsonToken::EndObject => {
+                    let pub_key = match pub_key.as_ref().ok_or(Some(OOB::Reject))? {
+                        AltResult::First(key) => Some(key.as_slice()),
+                        AltResult::Second(_) => None,
+                    };
+                    *destination = Some(
+                        self.summary(pub_key, clist.take(), *current)
+                            .ok_or(Some(OOB::Reject))?,
+                    );
+                    return Ok(());
+                }
+                _ => return Err(Some(OOB::Reject)),
+            }
+            break Err(None);
+        }
+    }
+}
+
+// Check that the key for the path is one of the signers of the transaction, and show the user
+// which of the signers it is; with several paths, address is the number of the path.
+fn check_signer(signers: &SignerKeys, path: &[u32], address: Option<usize>) -> Option<()> {
+    with_public_keys(path, false, |key: &_, _: &PKH| {
+        try_option(|| -> Option<()> {
+            let mut title: ArrayString<22> = ArrayString::new();
+            let mut which: ArrayString<22> = ArrayString::new();
+            match address {
+                Some(n) => {
+                    write!(mk_prompt_write(&mut title), "Your Signer {}", n).ok()?;
+                    write!(mk_prompt_write(&mut which), "Address {}", n).ok()?;
+                }
+                None => {
+                    write!(mk_prompt_write(&mut title), "Your Signer").ok()?;
+                    write!(mk_prompt_write(&mut which), "this address").ok()?;
+                }
+            }
+            match signers.position(ed25519_public_key_bytes(key)) {
+                Some(i) => scroller(&title, |w| Ok(write!(w, "{} of {}", i + 1, signers.count)?)),
+                None if signers.count > signers.keys.len() => scroller("WARNING", |w| {
+                    Ok(write!(w, "Too many signers for Ledger to check that the key for {} is one of them.", which)?)
+                }),
+                None => scroller("WARNING", |w| {
+                    Ok(write!(w, "The key for {} is not one of the signers of this transaction. Its signature does not grant any of the capabilities shown.", which)?)
+                }),
+            }
+        }())
+    })
+    .ok()
+}
+
+// The names in a transfer which are longer than these are not kept, and the transfer is then not
+// cross checked; on the Nano S the accounts are only kept up to the length of a k: account.
+#[cfg(target_os = "nanos")]
+const TRANSFER_ACCOUNT_SIZE: usize = 66;
+#[cfg(not(target_os = "nanos"))]
+const TRANSFER_ACCOUNT_SIZE: usize = 256;
+const TRANSFER_MODULE_SIZE: usize = 64;
+const TRANSFER_CHAIN_SIZE: usize = 8;
+
+// The transfer done by either the code or a transfer capability, to be compared field by field.
+// Amounts are kept in their normal form, as eg the cap may have 11 where the code has 11.0.
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct TransferSummary {
+    module: ArrayString<TRANSFER_MODULE_SIZE>,
+    sender: ArrayString<TRANSFER_ACCOUNT_SIZE>,
+    receiver: ArrayString<TRANSFER_ACCOUNT_SIZE>,
+    amount: Decimal,
+    // The target chain of a cross-chain transfer
+    target_chain: Option<ArrayString<TRANSFER_CHAIN_SIZE>>,
+}
+
+impl TransferSummary {
+    fn new(
+        module: &str,
+        sender: &str,
+        receiver: &str,
+        amount: Decimal,
+        target_chain: Option<&str>,
+    ) -> Option<TransferSummary> {
+        Some(TransferSummary {
+            module: ArrayString::from(module).ok()?,
+            sender: ArrayString::from(sender).ok()?,
+            receiver: ArrayString::from(receiver).ok()?,
+            amount,
+            target_chain: match target_chain {
+                Some(chain) => Some(ArrayString::from(chain).ok()?),
+                None => None,
+            },
+        })
+    }
+}
+
+// The amount argument of a transfer capability is either a JSON number, or a Pact decimal as
+// {"decimal":"1.0"}
+fn parse_cap_amount(amount: &str) -> Option<Decimal> {
+    match amount
+        .strip_prefix("{\"decimal\":\"")
+        .and_then(|a| a.strip_suffix("\"}"))
+    {
+        Some(decimal) => Decimal::parse_plain(decimal),
+        None => Decimal::parse(amount),
+    }
+}
+
+// The number of decimal places allowed by the tokens whose precision is known, by module; the
+// amounts of other tokens are only checked to be decimals.
+const TOKEN_PRECISIONS: &[(&str, u32)] = &[("coin", KDA_PRECISION)];
+
+fn token_precision(module: &str) -> Option<u32> {
+    TOKEN_PRECISIONS
+        .iter()
+        .find(|(m, _)| *m == module)
+        .map(|(_, precision)| *precision)
+}
+
+// Whether the amount argument of a transfer capability has no more decimal places than its token
+// allows; an amount which is not valid is shown as it is, and is not checked here.
+fn cap_amount_fits_precision(module: &str, amount: &str) -> bool {
+    match (token_precision(module), parse_cap_amount(amount)) {
+        (Some(precision), Some(amount)) => amount.with_max_precision(precision).is_some(),
+        _ => true,
+    }
+}
+
+fn show_precision_warning(module: &str) -> Option<()> {
+    scroller("WARNING", |w| {
+        Ok(write!(
+            w,
+            "The amount has more decimal places than {} allows. The transfer will fail.",
+            module
+        )?)
+    })
+}
+
+// Write the amount argument of a transfer capability in the normal form, or as it is if it is not
+// a valid amount
+fn write_cap_amount<W: Write>(w: &mut W, amount: &str) -> Result<(), ScrollerError> {
+    match parse_cap_amount(amount) {
+        Some(amount) => write!(w, "{}", amount)?,
+        None => write!(w, "{}", amount)?,
+    }
+    Ok(())
+}
+
+// The TransferSummary of a transfer capability, from the JSON rendering of its arguments; the
+// target chain is only given for TRANSFER_XCHAIN.
+fn transfer_cap_summary(
+    module: &str,
+    sender: &str,
+    receiver: &str,
+    amount: &str,
+    target_chain: Option<&str>,
+) -> TransferCaps {
+    match (|| {
+        TransferSummary::new(
+            module,
+            json_string(sender)?,
+            json_string(receiver)?,
+            parse_cap_amount(amount)?,
+            match target_chain {
+                Some(chain) => Some(json_string(chain)?),
+                None => None,
+            },
+        )
+    })() {
+        Some(transfer) => TransferCaps::Single(transfer),
+        None => TransferCaps::Unmatchable,
+    }
+}
+
+// The nonce is only shown in expert mode, if it fits
+const NONCE_SIZE: usize = 64;
+
+// The hash of the transaction, the keys of its signers and the totals of its transfers, or the
+// reason it cannot be signed
+type TxReview = Result<(Zeroizing<Base64Hash<32>>, SignerKeys, TransferTotals), KadenaError>;
+
+pub type SignTxReviewImplT = impl InterpParser<KadenaCmdJson, Returning = TxReview>;
+
+// Show the transaction to the user, and compute its hash; the signers' keys are returned to be
+// checked against the key we sign with, and the transfer totals to be shown before signing. With
+// the key for the path, only its signer is shown in full.
+const SIGN_TX_REVIEW_IMPL: SignTxReviewImplT = sign_tx_review_impl(None);
+
+const fn sign_tx_review_impl(key: Option<[u8; 32]>) -> SignTxReviewImplT {
+    Action(
+        // Calculate the hash of the transaction
+        ObserveLengthedBytes(
+            Hasher::new,
+            Hasher::update,
+            Json(Action(
+                Preaction(
+                    || -> Option<()> { scroller("Signing", |w| Ok(write!(w, "Transaction")?)) },
+                    KadenaCmdInterp {
+                        field_nonce: Action(
+                            Alt(JsonStringAccumulate::<NONCE_SIZE>, DropInterp),
+                            mkvfn(
+                                |nonce: &AltResult<ArrayVec<u8, NONCE_SIZE>, ()>, _| -> Option<()> {
+                                    if !expert_mode() {
+                                        return Some(());
+                                    }
+                                    scroller("Nonce", |w| match nonce {
+                                        AltResult::First(nonce)
+                                            if can_show_unescaped(nonce.as_slice()) =>
+                                        {
+                                            write_unescaped(w, nonce.as_slice())
+                                        }
+                                        _ => Ok(write!(w, "cannot be displayed on Ledger")?),
+                                    })
+                                },
+                            ),
+                        ),
+                        field_meta: META_ACTION,
+                        field_payload: Alt(
+                            PayloadInterp {
+                                field_exec: CommandInterp {
+                                    field_code: CODE_ACTION,
+                                    field_data: DataInterp,
+                                },
+                            },
+                            ContPayloadInterp {
+                                field_cont: CONT_ACTION,
+                            },
+                        ),
+                        field_signers: MoveAction(
+                            SubInterpM::<_, SignersSummary>::new(SignerSummaryInterp { key }),
+                            mkmvfn(
+                                |signers: SignersSummary,
+                                 dest: &mut Option<SignersSummary>|
+                                 -> Option<()> {
+                                    match signers.others {
+                                        0 => {}
+                                        1 => scroller("Also Signing", |w| {
+                                            Ok(write!(w, "1 other signer")?)
+                                        })?,
+                                        n => scroller("Also Signing", |w| {
+                                            Ok(write!(w, "{} other signers", n)?)
+                                        })?,
+                                    }
+                                    *dest = Some(signers);
+                                    Some(())
+                                },
+                            ),
+                        ),
+                        field_network_id: Action(
+                            Alt(JsonStringAccumulate::<32>, DropInterp),
+                            mkvfn(
+                                |mnet: &AltResult<ArrayVec<u8, 32>, ()>, dest: &mut Option<()>| {
+                                    *dest = Some(());
+                                    match mnet {
+                                        AltResult::First(net) => scroller("On Network", |w| {
+                                            Ok(write!(w, "{}", from_utf8(net.as_slice())?)?)
+                                        }),
+                                        _ => Some(()), // Ignore null
+                                    }
+                                },
+                            ),
+                        ),
+                    },
+                ),
+                mkfn(
+                    |cmd: &KadenaCmd<
+                        _,
+                        Option<Result<Option<u64>, KadenaError>>,
+                        Option<SignersSummary>,
+                        Option<
+                            AltResult<
+                                Payload<Option<Command<Option<DataEntries>, Option<CodeSummary>>>>,
+                                _,
+                            >,
+                        >,
+                        _,
+                    >,
+                     dest: &mut Option<Result<(SignerKeys, TransferTotals), KadenaError>>| {
+                        let too_long = match cmd.field_payload {
+                            Some(AltResult::Second(ContPayload {
+                                field_cont: Some(Err(e)),
+                            })) => Some(e),
+                            _ => match cmd.field_meta {
+                                Some(Err(e)) => Some(e),
+                                _ => None,
+                            },
+                        };
+                        if let Some(e) = too_long {
+                            return reject(dest, e);
+                        }
+                        let (code, data) = match cmd.field_payload {
+                            Some(AltResult::First(Payload {
+                                field_exec:
+                                    Some(Command {
+                                        field_code: Some(ref code),
+                                        ref field_data,
+                                    }),
+                            })) => (Some(code), field_data.as_ref()),
+                            _ => (None, None),
+                        };
+                        if let Some(CodeSummary::Transfer(_, Some(guard))) = code {
+                            let keyset_key = guard
+                                .keyset_name
+                                .as_ref()
+                                .and_then(|name| data?.k_account_key(name));
+                            if keyset_key != Some(guard.key) {
+                                scroller("WARNING", |w| {
+                                    Ok(write!(w, "The keyset of the k: account receiving the transfer is not its own key. The account may be controlled by someone else.")?)
+                                })?;
+                            }
+                        }
+                        if let (Some(CodeSummary::Transfer(transfer, _)), Some(signers)) =
+                            (code, cmd.field_signers.as_ref())
+                        {
+                            // The transfer may be granted by one of the signers which were not
+                            // shown
+                            let granted_by_others = signers.others > 0
+                                && signers.transfer_caps == TransferCaps::NoTransfer;
+                            if signers.coverage != CapabilityCoverage::NoCaps
+                                && signers.transfer_caps != TransferCaps::Single(*transfer)
+                                && !granted_by_others
+                            {
+                                scroller("WARNING", |w| {
+                                    Ok(write!(w, "The transfer made by the code does not match the transfer capabilities. The transaction may fail or do something other than the capabilities shown.")?)
+                                })?;
+                            }
+                        }
+                        if let (Some(Ok(Some(creation_time))), Some(Some(timeout))) = (
+                            cmd.field_meta,
+                            cmd.field_signers.as_ref().map(|s| s.earliest_timeout),
+                        ) {
+                            if timeout <= creation_time {
+                                scroller("WARNING", |w| {
+                                    Ok(write!(w, "A timeout of the capabilities is not after the creation time of the transaction. The sale has already expired.")?)
+                                })?;
+                            }
+                        }
+                        let code_shown = !matches!(code, Some(CodeSummary::NotShown));
+                        match cmd.field_signers.as_ref().map(|signers| &signers.coverage) {
+                            Some(CapabilityCoverage::Full) if code_shown => {}
+                            Some(CapabilityCoverage::Full | CapabilityCoverage::HasFallback) => {
+                                scroller("WARNING", |w| {
+                                    Ok(write!(w, "Transaction too large for Ledger to display.  PROCEED WITH GREAT CAUTION.  Do you want to continue?")?)
+                                })?;
+                            }
+                            _ => {
+                                scroller("WARNING", |w| {
+                                    Ok(write!(w, "UNSAFE TRANSACTION. This transaction's code was not recognized and does not limit capabilities for all signers. Signing this transaction may make arbitrary actions on the chain including loss of all funds.")?)
+                                })?;
+                            }
+                        }
+                        *dest = Some(Ok(match cmd.field_signers.as_ref() {
+                            Some(signers) => (signers.keys.clone(), signers.totals.clone()),
+                            None => (Summable::zero(), Summable::zero()),
+                        }));
+                        Some(())
+                    },
+                ),
+            )),
+            true,
+        ),
+        // Ask the user if they accept the transaction body's hash
+        mkfn(
+            |(signers, mut hasher): &(
+                Option<Result<(SignerKeys, TransferTotals), KadenaError>>,
+                Blake2b,
+            ),
+             destination: &mut Option<TxReview>| {
+                let (keys, totals) = match signers.as_ref()? {
+                    Ok(signers) => signers.clone(),
+                    Err(e) => return reject(destination, *e),
+                };
+                let the_hash: Zeroizing<Base64Hash<32>> = hasher.finalize();
+                show_hash("Transaction hash", &the_hash.0, false)?;
+                *destination = Some(Ok((the_hash, keys, totals)));
+                Some(())
+            },
+        ),
+    )
+}
+
+pub static SIGN_IMPL: SignImplT = Action(
+    (
+        SIGN_TX_REVIEW_IMPL,
+        MoveAction(
+            SubInterp(DefaultInterp),
+            // And ask the user if this is the key the meant to sign with:
+            mkmvfn(
+                |path: ArrayVec<u32, 10>,
+                 destination: &mut Option<Result<ArrayVec<u32, 10>, KadenaError>>| {
+                    let path = check_path(path);
+                    if let Ok(ref path) = path {
+                        with_public_keys(path, false, |_, pkh: &PKH| {
+                            try_option(|| -> Option<()> {
+                                scroller("Sign for Address", |w| Ok(write!(w, "{pkh}")?))?;
+                                Some(())
+                            }())
+                        })
+                        .ok()?;
+                    }
+                    *destination = Some(path);
+                    Some(())
+                },
+            ),
+        ),
+    ),
+    mkfn(
+        |(review, path): &(
+            Option<TxReview>,
+            Option<Result<ArrayVec<u32, 10>, KadenaError>>,
+        ),
+         destination: &mut Option<ApduResult<128>>| {
+            let (hash, signers, totals) = match review.as_ref()? {
+                Ok(review) => review,
+                Err(e) => return reject(destination, *e),
+            };
+            let path = match path.as_ref()? {
+                Ok(path) => path,
+                Err(e) => return reject(destination, *e),
+            };
+            check_signer(signers, path, None)?;
+            show_transfer_totals(totals)?;
+            #[allow(clippy::needless_borrow)] // Needed for nanos
+            if final_accept_prompt(&[&"Sign Transaction?"]).is_none() {
+                return reject(destination, KadenaError::UserRejected);
+            }
+
+            // By the time we get here, we've approved and just need to do the signature.
+            let sig = eddsa_sign(path, false, &hash.0[..]).ok()?;
+            let mut rv = ArrayVec::<u8, 128>::new();
+            rv.try_extend_from_slice(&sig.0[..]).ok()?;
+            *destination = Some(Ok(rv));
+            Some(())
+        },
+    ),
+);
+
+// A public key and a signature for each of the paths
+pub const SIGN_MULTI_RESPONSE_SIZE: usize = MAX_SIGN_PATHS * (32 + 64);
+
+pub type SignMultiImplT =
+    impl InterpParser<SignMultiParameters, Returning = ApduResult<SIGN_MULTI_RESPONSE_SIZE>>;
+
+type SignPaths = ArrayVec<ArrayVec<u32, 10>, MAX_SIGN_PATHS>;
+
+pub static SIGN_MULTI_IMPL: SignMultiImplT = Action(
+    (
+        SIGN_TX_REVIEW_IMPL,
+        MoveAction(
+            SubInterp(SubInterp(DefaultInterp)),
+            // Ask the user to confirm each of the keys to sign with
+            mkmvfn(
+                |paths: SignPaths, destination: &mut Option<Result<SignPaths, KadenaError>>| {
+                    if paths.is_empty() {
+                        return None;
+                    }
+                    for (i, path) in paths.iter().enumerate() {
+                        if !path.starts_with(&BIP32_PREFIX[0..2]) || paths[..i].contains(path) {
+                            return reject(destination, KadenaError::InvalidPath);
+                        }
+                        let mut title: ArrayString<22> = ArrayString::new();
+                        write!(mk_prompt_write(&mut title), "Sign for Address {}", i + 1).ok()?;
+                        with_public_keys(path, false, |_, pkh: &PKH| {
+                            try_option(|| -> Option<()> {
+                                scroller(&title, |w| Ok(write!(w, "{pkh}")?))?;
+                                Some(())
+                            }())
+                        })
+                        .ok()?;
+                    }
+                    *destination = Some(Ok(paths));
+                    Some(())
+                },
+            ),
+        ),
+    ),
+    mkfn(
+        |(review, paths): &(Option<TxReview>, Option<Result<SignPaths, KadenaError>>),
+         destination: &mut Option<ApduResult<SIGN_MULTI_RESPONSE_SIZE>>| {
+            let (hash, signers, totals) = match review.as_ref()? {
+                Ok(review) => review,
+                Err(e) => return reject(destination, *e),
+            };
+            let paths = match paths.as_ref()? {
+                Ok(paths) => paths,
+                Err(e) => return reject(destination, *e),
+            };
+            for (i, path) in paths.iter().enumerate() {
+                check_signer(signers, path, Some(i + 1))?;
+            }
+            show_transfer_totals(totals)?;
+            #[allow(clippy::needless_borrow)] // Needed for nanos
+            if final_accept_prompt(&[&"Sign Transaction?"]).is_none() {
+                return reject(destination, KadenaError::UserRejected);
+            }
+
+            let mut rv = ArrayVec::new();
+            for path in paths {
+                with_public_keys(path, false, |key: &_, _: &PKH| {
+                    try_option(|| -> Option<()> {
+                        rv.try_extend_from_slice(ed25519_public_key_bytes(key)).ok()
+                    }())
+                })
+                .ok()?;
+                let sig = eddsa_sign(path, false, &hash.0[..]).ok()?;
+                rv.try_extend_from_slice(&sig.0[..]).ok()?;
+            }
+            *destination = Some(Ok(rv));
+            Some(())
+        },
+    ),
+);
+
+// Show the hash in the format chosen in the settings; unless `always`, it is hidden outside of
+// expert mode when the setting is expert only for JSON transactions
+fn show_hash(title: &str, hash: &[u8; 32], always: bool) -> Option<()> {
+    match Settings.hash_display() {
+        HashDisplay::Hex => scroller(title, |w| {
+            for b in hash {
+                write!(w, "{:02x}", b)?;
+            }
+            Ok(())
+        }),
+        HashDisplay::JsonExpertOnly if !always && !expert_mode() => Some(()),
+        _ => scroller(title, |w| Ok(write!(w, "{}", Base64Hash(*hash))?)),
+    }
+}
+
+// Expert mode adds the number of seconds to the validity period and creation time
+fn show_ttl(ttl: &[u8]) -> Option<()> {
+    scroller("Valid For", |w| match parse_seconds(ttl) {
+        Some(seconds) => {
+            write_duration(w, seconds)?;
+            if expert_mode() {
+                write!(w, " ({} seconds)", from_utf8(ttl)?)?;
+            }
+            Ok(())
+        }
+        None => Ok(write!(w, "{} seconds", from_utf8(ttl)?)?),
+    })
+}
+
+fn show_creation_time(creation_time: &[u8]) -> Option<()> {
+    scroller("Created At", |w| match parse_seconds(creation_time) {
+        Some(t) => {
+            write_utc_date(w, t)?;
+            if expert_mode() {
+                write!(w, " ({})", from_utf8(creation_time)?)?;
+            }
+            Ok(())
+        }
+        None => Ok(write!(w, "{}", from_utf8(creation_time)?)?),
+    })
+}
+
+// The coin contract limits account names to 256 characters; a longer sender is not shown. The
+// sender is on the stack twice while the meta is parsed, as it is accumulated and then returned,
+// so the Nano S only keeps up to the length of a k: account, ie 2 * 66 bytes rather than 512.
+#[cfg(target_os = "nanos")]
+const SENDER_SIZE: usize = 66;
+#[cfg(not(target_os = "nanos"))]
+const SENDER_SIZE: usize = 256;
+
+// The ttl and creation time are integers; longer ones are not shown
+const TIME_SIZE: usize = 20;
+
+const META_ACTION: Action<
+    Alt<
+        MetaInterp<
+            Alt<JsonStringAccumulate<32_usize>, DropInterp>,
+            Alt<JsonStringAccumulate<SENDER_SIZE>, DropInterp>,
+            Alt<JsonStringAccumulate<100_usize>, DropInterp>,
+            Alt<JsonStringAccumulate<100_usize>, DropInterp>,
+            Alt<JsonStringAccumulate<TIME_SIZE>, DropInterp>,
+            Alt<JsonStringAccumulate<TIME_SIZE>, DropInterp>,
+        >,
+        DropInterp,
+    >,
+    fn(
+        &AltResult<
+            Meta<
+                Option<AltResult<ArrayVec<u8, 32_usize>, ()>>,
+                Option<AltResult<ArrayVec<u8, SENDER_SIZE>, ()>>,
+                Option<AltResult<ArrayVec<u8, 100_usize>, ()>>,
+                Option<AltResult<ArrayVec<u8, 100_usize>, ()>>,
+                Option<AltResult<ArrayVec<u8, TIME_SIZE>, ()>>,
+                Option<AltResult<ArrayVec<u8, TIME_SIZE>, ()>>,
+            >,
+            (),
+        >,
+        &mut Option<Result<Option<u64>, KadenaError>>,
+    ) -> Option<()>,
+> = Action(
+    Alt(
+        MetaInterp {
+            field_chain_id: Alt(JsonStringAccumulate::<32>, DropInterp),
+            field_sender: Alt(JsonStringAccumulate::<SENDER_SIZE>, DropInterp),
+            field_gas_limit: Alt(JsonStringAccumulate::<100>, DropInterp),
+            field_gas_price: Alt(JsonStringAccumulate::<100>, DropInterp),
+            field_ttl: Alt(JsonStringAccumulate::<TIME_SIZE>, DropInterp),
+            field_creation_time: Alt(JsonStringAccumulate::<TIME_SIZE>, DropInterp),
+        },
+        DropInterp,
+    ),
+    // Returns the creation time, to check the timeouts of the capabilities against; the chain and
+    // gas are needed to sign, so they must fit
+    mkfn(|v, dest| match v {
+        AltResult::First(Meta {
+            ref field_chain_id,
+            ref field_sender,
+            ref field_gas_limit,
+            ref field_gas_price,
+            ref field_ttl,
+            ref field_creation_time,
+        }) => {
+            let (chain, gas_limit, gas_price) =
+                match (field_chain_id, field_gas_limit, field_gas_price) {
+                    (
+                        Some(AltResult::First(chain)),
+                        Some(AltResult::First(gas_limit)),
+                        Some(AltResult::First(gas_price)),
+                    ) => (chain, gas_limit, gas_price),
+                    (Some(AltResult::Second(_)), _, _)
+                    | (_, Some(AltResult::Second(_)), _)
+                    | (_, _, Some(AltResult::Second(_))) => {
+                        return reject(dest, KadenaError::FieldTooLong)
+                    }
+                    _ => return None,
+                };
+            scroller("On Chain", |w| {
+                Ok(write!(w, "{}", from_utf8(chain.as_slice())?)?)
+            })?;
+            scroller("Using Gas", |w| {
+                Ok(write!(
+                    w,
+                    "at most {} at price {}",
+                    from_utf8(gas_limit.as_slice())?,
+                    from_utf8(gas_price.as_slice())?
+                )?)
+            })?;
+            show_max_fee(
+                from_utf8(gas_limit.as_slice()).ok()?,
+                from_utf8(gas_price.as_slice()).ok()?,
+            )?;
+            match field_sender {
+                Some(AltResult::First(sender)) => {
+                    scroller_paginated("Gas Payer", |w| write_account(w, sender.as_slice()))?
+                }
+                Some(AltResult::Second(_)) => scroller("Gas Payer", |w| {
+                    Ok(write!(w, "cannot be displayed on Ledger")?)
+                })?,
+                None => {}
+            }
+            match field_ttl {
+                Some(AltResult::First(ttl)) => show_ttl(ttl.as_slice())?,
+                Some(AltResult::Second(_)) => scroller("Valid For", |w| {
+                    Ok(write!(w, "cannot be displayed on Ledger")?)
+                })?,
+                None => {}
+            }
+            *dest = Some(Ok(None));
+            match field_creation_time {
+                Some(AltResult::First(creation_time)) => {
+                    show_creation_time(creation_time.as_slice())?;
+                    *dest = Some(Ok(parse_seconds(creation_time.as_slice())));
+                }
+                Some(AltResult::Second(_)) => scroller("Created At", |w| {
+                    Ok(write!(w, "cannot be displayed on Ledger")?)
+                })?,
+                None => {}
+            }
+            Some(())
+        }
+        _ => {
+            *dest = Some(Ok(None));
+            scroller("CAUTION", |w| {
+                Ok(write!(w, "'meta' field of transaction not recognized")?)
+            })
+        }
+    }),
+);
+
+// The code is accumulated in this ArrayVec for display; if it does not fit then its start is shown
+// followed by its hash. The Nano S size fits the coin transfer forms with k: accounts.
+#[cfg(target_os = "nanos")]
+pub const CODE_ARRAY_SIZE: usize = 256;
+#[cfg(not(target_os = "nanos"))]
+pub const CODE_ARRAY_SIZE: usize = 1024;
+
+// How much of the code is shown when it is not shown in full, the same on every device
+const CODE_START_SIZE: usize = 128;
+
+// The code string is taken one token at a time, and a token is at most a chunk of the APDU; were
+// one larger, the Alt in CODE_ACTION would fall back to not showing the code
+const CODE_TOKEN_SIZE: usize = 256;
+
+pub struct CodeText {
+    text: ArrayVec<u8, CODE_ARRAY_SIZE>,
+    // The code did not fit in text, which only has its start
+    truncated: bool,
+    // The blake2b hash of the whole code as it is in the JSON string, escapes included
+    hash: [u8; 32],
+}
+
+pub struct CodeInterp;
+
+impl ParserCommon<JsonString> for CodeInterp {
+    type State = (
+        <JsonStringAccumulate<CODE_TOKEN_SIZE> as ParserCommon<JsonString>>::State,
+        Option<ArrayVec<u8, CODE_TOKEN_SIZE>>,
+        Blake2b,
+    );
+    type Returning = CodeText;
+    fn init(&self) -> Self::State {
+        (
+            <JsonStringAccumulate<CODE_TOKEN_SIZE> as ParserCommon<JsonString>>::init(
+                &JsonStringAccumulate,
+            ),
+            None,
+            Hasher::new(),
+        )
+    }
+}
+
+impl JsonInterp<JsonString> for CodeInterp {
+    #[inline(never)]
+    fn parse(
+        &self,
+        (ref mut state, ref mut token_text, ref mut hasher): &mut Self::State,
+        token: JsonToken<'_>,
+        destination: &mut Option<Self::Returning>,
+    ) -> Result<(), Option<OOB>> {
+        let rv = <JsonStringAccumulate<CODE_TOKEN_SIZE> as JsonInterp<JsonString>>::parse(
+            &JsonStringAccumulate,
+            state,
+            token,
+            token_text,
+        );
+        set_from_thunk(destination, || {
+            Some(CodeText {
+                text: ArrayVec::new(),
+                truncated: false,
+                hash: [0; 32],
+            })
+        });
+        let code = destination.as_mut().ok_or(Some(OOB::Reject))?;
+        // The accumulated text of the token is moved on, so that the next token has room
+        if let Some(token_text) = token_text.as_mut() {
+            hasher.update(token_text.as_slice());
+            let room = code.text.remaining_capacity();
+            if token_text.len() > room {
+                code.truncated = true;
+            }
+            let n = token_text.len().min(room);
+            code.text
+                .try_extend_from_slice(&token_text[..n])
+                .map_err(|_| Some(OOB::Reject))?;
+            token_text.clear();
+        }
+        rv?;
+        let hash: Base64Hash<32> = hasher.finalize();
+        code.hash = hash.0;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum CodeSummary {
+    NotShown,
+    Shown,
+    // The code is one of the canonical transfers of coin or a token
+    Transfer(TransferSummary, Option<KAccountGuard>),
+}
+
+// The k: account receiving a transfer-create or transfer-crosschain, and the name of the keyset it
+// is guarded by; the name is None if it is too long to be kept.
+#[derive(Debug)]
+struct KAccountGuard {
+    key: [u8; 32],
+    keyset_name: Option<ArrayVec<u8, KEYSET_NAME_SIZE>>,
+}
+
+// The modules whose transfers are recognised, as for the transfer capabilities: coin and the
+// fungible-v2 tokens in a namespace
+fn is_token_module(module: &str) -> bool {
+    module == "coin" || cap_name_matches("*.*", module)
+}
+
+fn k_account_guard(transfer: &TransferCode) -> Option<KAccountGuard> {
+    let key = parse_pub_key(transfer.receiver.strip_prefix("k:")?.as_bytes())?;
+    let keyset_name = transfer.keyset_name?;
+    Some(KAccountGuard {
+        key,
+        keyset_name: ArrayVec::try_from(keyset_name.as_bytes()).ok(),
+    })
+}
+
+const CODE_ACTION: Action<
+    Alt<CodeInterp, DropInterp>,
+    fn(&AltResult<CodeText, ()>, &mut Option<CodeSummary>) -> Option<()>,
+> = Action(
+    Alt(CodeInterp, DropInterp),
+    mkfn(|code, dest| {
+        match code {
+            AltResult::First(code) if !code.truncated && can_show_unescaped(&code.text) => {
+                scroller_paginated("Code", |w| write_unescaped(w, code.text.as_slice()))?;
+                let transfer = from_utf8(code.text.as_slice())
+                    .ok()
+                    .and_then(parse_transfer_code)
+                    .filter(|t| is_token_module(t.module))
+                    .and_then(|t| {
+                        let summary = TransferSummary::new(
+                            t.module,
+                            t.sender,
+                            t.receiver,
+                            t.amount,
+                            t.target_chain,
+                        )?;
+                        Some((summary, k_account_guard(&t)))
+                    });
+                *dest = Some(match transfer {
+                    Some((summary, guard)) => CodeSummary::Transfer(summary, guard),
+                    None => CodeSummary::Shown,
+                });
+            }
+            AltResult::First(code) => {
+                let start = &code.text[..code.text.len().min(CODE_START_SIZE)];
+                scroller_paginated("Code", |w| {
+                    write_unescaped_start(w, start)?;
+                    Ok(write!(w, "...")?)
+                })?;
+                show_hash("Code hash", &code.hash, true)?;
+                *dest = Some(CodeSummary::NotShown);
+            }
+            AltResult::Second(_) => {
+                scroller("Code", |w| Ok(write!(w, "cannot be displayed on Ledger")?))?;
+                *dest = Some(CodeSummary::NotShown);
+            }
+        }
+        Some(())
+    }),
+);
+
+// The keysets in the data of the transaction, ie {"keys":["<key>", ...],"pred":"keys-all"}, are
+// shown, as eg the receiver of a transfer-create is guarded by a keyset read from the data. The
+// keys which are not ed25519 keys are counted, and shown with a warning; any other value is dropped.
+#[cfg(target_os = "nanos")]
+const MAX_KEYSET_KEYS: usize = 3;
+#[cfg(not(target_os = "nanos"))]
+const MAX_KEYSET_KEYS: usize = 10;
+
+#[cfg(target_os = "nanos")]
+const MAX_DATA_ENTRIES: usize = 4;
+#[cfg(not(target_os = "nanos"))]
+const MAX_DATA_ENTRIES: usize = 16;
+
+const KEYSET_NAME_SIZE: usize = 32;
+const KEYSET_PRED_SIZE: usize = 64;
+
+// The first ed25519 keys of a keyset, the number of them, and the number of other keys, ie keys
+// which are not 64 hex digits
+#[derive(Debug, Clone)]
+pub struct KeysetKeys {
+    keys: ArrayVec<[u8; 32], MAX_KEYSET_KEYS>,
+    count: usize,
+    others: usize,
+}
+
+impl Summable<KeysetKeys> for KeysetKeys {
+    fn zero() -> Self {
+        KeysetKeys {
+            keys: ArrayVec::new(),
+            count: 0,
+            others: 0,
+        }
+    }
+    fn add_and_set(&mut self, other: &KeysetKeys) {
+        for k in other.keys.iter() {
+            if self.keys.try_push(*k).is_err() {
+                break;
+            }
+        }
+        self.count += other.count;
+        self.others += other.others;
+    }
+}
+
+// The names of the entries of the data, with the key if the value is a keyset of that single key,
+// as for a k: account
+#[derive(Debug)]
+pub struct DataEntries {
+    entries: ArrayVec<(ArrayVec<u8, KEYSET_NAME_SIZE>, Option<[u8; 32]>), MAX_DATA_ENTRIES>,
+    // Whether all the entries of the data could be kept
+    complete: bool,
+}
+
+impl DataEntries {
+    // If a name is repeated, the last value is the one read by Pact
+    fn k_account_key(&self, name: &[u8]) -> Option<[u8; 32]> {
+        if !self.complete {
+            return None;
+        }
+        self.entries
+            .iter()
+            .rev()
+            .find(|(n, _)| n.as_slice() == name)?
+            .1
+    }
+}
+
+type KeysetKeyInterpT = Action<
+    Alt<JsonStringAccumulate<64>, DropInterp>,
+    fn(&AltResult<ArrayVec<u8, 64>, ()>, &mut Option<KeysetKeys>) -> Option<()>,
+>;
+
+type KeysetNameInterpT = Alt<JsonStringAccumulate<KEYSET_NAME_SIZE>, DropInterp>;
+type KeysetNameSchema = Alt<JsonString, JsonString>;
+const KEYSET_NAME_INTERP: KeysetNameInterpT = Alt(JsonStringAccumulate, DropInterp);
+
+type DataValueInterpT = Alt<
+    KeysetInterp<SubInterpM<KeysetKeyInterpT, KeysetKeys>, JsonStringAccumulate<KEYSET_PRED_SIZE>>,
+    DropInterp,
+>;
+type DataValueSchema = Alt<KeysetSchema, JsonAny>;
+const DATA_VALUE_INTERP: DataValueInterpT = Alt(
+    KeysetInterp {
+        field_keys: SubInterpM::new(Action(
+            Alt(JsonStringAccumulate::<64>, DropInterp),
+            mkfn(
+                |key: &AltResult<ArrayVec<u8, 64>, ()>, dest: &mut Option<KeysetKeys>| {
+                    let mut keys: KeysetKeys = Summable::zero();
+                    match key {
+                        AltResult::First(key) if parse_pub_key(key).is_some() => {
+                            keys.keys.push(parse_pub_key(key)?);
+                            keys.count = 1;
+                        }
+                        _ => keys.others = 1,
+                    }
+                    *dest = Some(keys);
+                    Some(())
+                },
+            ),
+        )),
+        field_pred: JsonStringAccumulate::<KEYSET_PRED_SIZE>,
+    },
+    DropInterp,
+);
+
+fn show_keyset(name: Option<&[u8]>, keys: &KeysetKeys, pred: &[u8]) -> Option<()> {
+    scroller_paginated("Keyset", |w| {
+        if let Some(name) = name {
+            write!(w, "{}: ", from_utf8(name)?)?;
+        }
+        write!(w, "{} [", from_utf8(pred)?)?;
+        for (i, key) in keys.keys.iter().enumerate() {
+            if i > 0 {
+                write!(w, ", ")?;
+            }
+            for b in key {
+                write!(w, "{:02x}", b)?;
+            }
+        }
+        if keys.count > keys.keys.len() {
+            write!(w, ", and {} more", keys.count - keys.keys.len())?;
+        }
+        let sep = if keys.count > 0 { ", and " } else { "" };
+        match keys.others {
+            0 => {}
+            1 => write!(w, "{}1 other key", sep)?,
+            n => write!(w, "{}{} other keys", sep, n)?,
+        }
+        write!(w, "]")?;
+        Ok(())
+    })?;
+    if keys.others > 0 {
+        scroller("WARNING", |w| {
+            Ok(write!(w, "The keyset has keys which are not ed25519 public keys. They cannot be shown on Ledger.")?)
+        })?;
+    }
+    Some(())
+}
+
+pub struct DataInterp;
+
+pub enum DataInterpState {
+    Start,
+    // The data is not an object
+    Drop(<DropInterp as ParserCommon<JsonAny>>::State),
+    Begin,
+    Name(<KeysetNameInterpT as ParserCommon<KeysetNameSchema>>::State),
+    NameSep,
+    Value(<DataValueInterpT as ParserCommon<DataValueSchema>>::State),
+    ValueSep,
+}
+
+impl ParserCommon<JsonAny> for DataInterp {
+    type State = (
+        DataInterpState,
+        Option<<KeysetNameInterpT as ParserCommon<KeysetNameSchema>>::Returning>,
+        Option<<DataValueInterpT as ParserCommon<DataValueSchema>>::Returning>,
+    );
+    type Returning = DataEntries;
+    fn init(&self) -> Self::State {
+        (DataInterpState::Start, None, None)
+    }
+}
+
+impl JsonInterp<JsonAny> for DataInterp {
+    #[inline(never)]
+    fn parse(
+        &self,
+        (ref mut state, ref mut name, ref mut value): &mut Self::State,
+        token: JsonToken<'_>,
+        destination: &mut Option<Self::Returning>,
+    ) -> Result<(), Option<OOB>> {
+        loop {
+            use DataInterpState::*;
+            match state {
+                Start => {
+                    set_from_thunk(destination, || {
+                        Some(DataEntries {
+                            entries: ArrayVec::new(),
+                            complete: true,
+                        })
+                    });
+                    if token == JsonToken::BeginObject {
+                        set_from_thunk(state, || Begin);
+                    } else {
+                        set_from_thunk(state, || {
+                            Drop(<DropInterp as ParserCommon<JsonAny>>::init(&DropInterp))
+                        });
+                        continue;
+                    }
+                }
+                Drop(ref mut s) => {
+                    <DropInterp as JsonInterp<JsonAny>>::parse(&DropInterp, s, token, &mut None)?;
+                    return Ok(());
+                }
+                Begin if token == JsonToken::EndObject => return Ok(()),
+                Begin => {
+                    set_from_thunk(state, || {
+                        Name(<KeysetNameInterpT as ParserCommon<KeysetNameSchema>>::init(
+                            &KEYSET_NAME_INTERP,
+                        ))
+                    });
+                    continue;
+                }
+                Name(ref mut s) => {
+                    <KeysetNameInterpT as JsonInterp<KeysetNameSchema>>::parse(
+                        &KEYSET_NAME_INTERP,
+                        s,
+                        token,
+                        name,
+                    )?;
+                    set_from_thunk(state, || NameSep);
+                }
+                NameSep if token == JsonToken::NameSeparator => {
+                    set_from_thunk(state, || {
+                        Value(<DataValueInterpT as ParserCommon<DataValueSchema>>::init(
+                            &DATA_VALUE_INTERP,
+                        ))
+                    });
+                }
+                Value(ref mut s) => {
+                    <DataValueInterpT as JsonInterp<DataValueSchema>>::parse(
+                        &DATA_VALUE_INTERP,
+                        s,
+                        token,
+                        value,
+                    )?;
+                    let name = match name.take() {
+                        Some(AltResult::First(name)) => Some(name),
+                        _ => None,
+                    };
+                    let single_key = match value.take() {
+                        Some(AltResult::First(Keyset {
+                            field_keys: Some(keys),
+                            field_pred: Some(pred),
+                        })) => {
+                            show_keyset(name.as_ref().map(|n| n.as_slice()), &keys, &pred)
+                                .ok_or(Some(OOB::Reject))?;
+                            match (pred.as_slice(), keys.count, keys.others) {
+                                (b"keys-all" | b"keys-any", 1, 0) => Some(keys.keys[0]),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    };
+                    // A name which is too long cannot be the name of a k: account's keyset
+                    if let Some(name) = name {
+                        let data = destination.as_mut().ok_or(Some(OOB::Reject))?;
+                        if data.entries.try_push((name, single_key)).is_err() {
+                            data.complete = false;
+                        }
+                    }
+                    set_from_thunk(state, || ValueSep);
+                }
+                ValueSep if token == JsonToken::ValueSeparator => {
+                    set_from_thunk(state, || {
+                        Name(<KeysetNameInterpT as ParserCommon<KeysetNameSchema>>::init(
+                            &KEYSET_NAME_INTERP,
+                        ))
+                    });
+                }
+                ValueSep if token == JsonToken::EndObject => return Ok(()),
+                _ => return Err(Some(OOB::Reject)),
+            }
+            break Err(None);
+        }
+    }
+}
+
+// Pact ids are base64url encoded hashes, so 43 characters in practice
+const PACT_ID_SIZE: usize = 64;
+
+const CONT_ACTION: Action<
+    ContinuationInterp<
+        Alt<JsonStringAccumulate<PACT_ID_SIZE>, DropInterp>,
+        Alt<JsonStringAccumulate<16_usize>, DropInterp>,
+        Alt<JsonStringAccumulate<5_usize>, DropInterp>,
+        DropInterp,
+        Alt<DropInterp, DropInterp>,
+    >,
+    fn(
+        &Continuation<
+            Option<AltResult<ArrayVec<u8, PACT_ID_SIZE>, ()>>,
+            Option<AltResult<ArrayVec<u8, 16_usize>, ()>>,
+            Option<AltResult<ArrayVec<u8, 5_usize>, ()>>,
+            Option<()>,
+            Option<AltResult<(), ()>>,
+        >,
+        &mut Option<Result<(), KadenaError>>,
+    ) -> Option<()>,
+> = Action(
+    ContinuationInterp {
+        field_pact_id: Alt(JsonStringAccumulate::<PACT_ID_SIZE>, DropInterp),
+        field_step: Alt(JsonStringAccumulate::<16>, DropInterp),
+        field_rollback: Alt(JsonStringAccumulate::<5>, DropInterp),
+        field_data: DropInterp,
+        field_proof: Alt(DropInterp, DropInterp),
+    },
+    mkvfn(|cont, dest| {
+        let (pact_id, step, rollback) =
+            match (&cont.field_pact_id, &cont.field_step, &cont.field_rollback) {
+                (
+                    Some(AltResult::First(pact_id)),
+                    Some(AltResult::First(step)),
+                    Some(AltResult::First(rollback)),
+                ) => (pact_id, step, rollback),
+                (Some(AltResult::Second(_)), _, _)
+                | (_, Some(AltResult::Second(_)), _)
+                | (_, _, Some(AltResult::Second(_))) => {
+                    return reject(dest, KadenaError::FieldTooLong)
+                }
+                _ => return None,
+            };
+        let pact_id = from_utf8(pact_id.as_slice()).ok()?;
+        let step = from_utf8(step.as_slice()).ok()?;
+        check_positive_integer(step)?;
+        let rollback = match rollback.as_slice() {
+            b"true" => true,
+            b"false" => false,
+            _ => return None,
+        };
+        scroller_paginated("Continuing Pact", |w| Ok(write!(w, "{}", pact_id)?))?;
+        scroller("Step", |w| Ok(write!(w, "{}", step)?))?;
+        scroller("Rollback", |w| {
+            Ok(write!(w, "{}", if rollback { "Yes" } else { "No" })?)
+        })?;
+        match cont.field_proof {
+            Some(AltResult::First(_)) => scroller("SPV Proof", |w| Ok(write!(w, "Attached")?))?,
+            _ => scroller("SPV Proof", |w| Ok(write!(w, "None")?))?,
+        }
+        *dest = Some(Ok(()));
+        Some(())
+    }),
+);
+
+#[derive(Debug, Clone, Copy)]
+enum CapCountData {
+    IsTransfer(TransferCaps),
+    IsUnknownCap,
+    // A labelled capability with a timeout, as a Unix time
+    IsTimedCap(u64),
+    CapCount {
+        total_caps: u16,
+        total_transfers: u16,
+        total_unknown: u16,
+        transfer_caps: TransferCaps,
+        earliest_timeout: Option<u64>,
+    },
+}
+
+fn earliest_timeout(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+impl Summable<CapCountData> for CapCountData {
+    fn add_and_set(&mut self, other: &CapCountData) {
+        if let CapCountData::CapCount {
+            total_caps,
+            total_transfers,
+            total_unknown,
+            transfer_caps,
+            earliest_timeout: timeout,
+        } = self
+        {
+            let add1 = |c: &mut u16| match c.checked_add(1) {
+                Some(v) => *c = v,
+                None => panic!("u16 overflow"),
+            };
+            add1(total_caps);
+            match other {
+                CapCountData::IsTransfer(t) => {
+                    add1(total_transfers);
+                    transfer_caps.add_and_set(t);
+                }
+                CapCountData::IsUnknownCap => add1(total_unknown),
+                CapCountData::IsTimedCap(t) => *timeout = earliest_timeout(*timeout, Some(*t)),
+                _ => {}
+            }
+        }
+    }
+    fn zero() -> Self {
+        CapCountData::CapCount {
+            total_caps: 0,
+            total_transfers: 0,
+            total_unknown: 0,
+            transfer_caps: TransferCaps::NoTransfer,
+            earliest_timeout: None,
+        }
+    }
+}
+
+// How an argument of a well-known capability is shown
+#[derive(Debug, Clone, Copy)]
+enum CapArgFormat {
+    // A JSON string, shown without the quotes
+    Account,
+    Chain,
+    Id,
+    // A JSON string, abbreviated to its first and last digits if it is long, as for the token ids
+    // of Marmalade
+    TokenId,
+    // An amount, in the normal form
+    Amount,
+    // A timeout as a Unix time, or 0 for no timeout
+    Timeout,
+    // A timeout as a block height
+    TimeoutBlock,
+    // Any other value, eg a guard or an integer, shown as its JSON
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CapKind {
+    // Shown with the title, and the arguments with their labels
+    Labelled,
+    // Shown as "Transfer N", and checked against the transfer made by the code. The module of the
+    // token is shown when the name has a "*".
+    Transfer,
+    TransferXChain,
+}
+
+struct CapDescriptor {
+    // The qualified name of the capability; a "*" matches any namespace or module name, in which
+    // case the name is shown too.
+    name: &'static str,
+    title: &'static str,
+    kind: CapKind,
+    // The label and format of each argument; a capability with another number of arguments is
+    // shown as an unknown capability. An empty label shows the argument alone.
+    args: &'static [(&'static str, CapArgFormat)],
+}
+
+// The capabilities which are shown with their arguments labelled rather than as unknown
+// capabilities. The first matching entry is used.
+const WELL_KNOWN_CAPS: &[CapDescriptor] = &[
+    CapDescriptor {
+        name: "coin.GAS",
+        title: "Paying Gas",
+        kind: CapKind::Labelled,
+        args: &[],
+    },
+    CapDescriptor {
+        name: "coin.TRANSFER",
+        title: "Transfer",
+        kind: CapKind::Transfer,
+        args: &[
+            ("sender", CapArgFormat::Account),
+            ("receiver", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+        ],
+    },
+    CapDescriptor {
+        name: "coin.TRANSFER_XCHAIN",
+        title: "Transfer",
+        kind: CapKind::TransferXChain,
+        args: &[
+            ("sender", CapArgFormat::Account),
+            ("receiver", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+            ("target chain", CapArgFormat::Chain),
+        ],
+    },
+    CapDescriptor {
+        name: "coin.ROTATE",
+        title: "Rotate for account",
+        kind: CapKind::Labelled,
+        args: &[("", CapArgFormat::Json)],
+    },
+    CapDescriptor {
+        name: "coin.DEBIT",
+        title: "Debit",
+        kind: CapKind::Labelled,
+        args: &[("account", CapArgFormat::Account)],
+    },
+    CapDescriptor {
+        name: "coin.CREDIT",
+        title: "Credit",
+        kind: CapKind::Labelled,
+        args: &[("account", CapArgFormat::Account)],
+    },
+    CapDescriptor {
+        name: "util.guards.GAS_PAYER",
+        title: "Gas Payer Station",
+        kind: CapKind::Labelled,
+        args: &[
+            ("user", CapArgFormat::Account),
+            ("limit", CapArgFormat::Json),
+            ("price", CapArgFormat::Amount),
+        ],
+    },
+    // The first version of Marmalade has timeouts as block heights, the second as times
+    CapDescriptor {
+        name: "marmalade.ledger.TRANSFER",
+        title: "Token Transfer",
+        kind: CapKind::Labelled,
+        args: &[
+            ("token", CapArgFormat::TokenId),
+            ("sender", CapArgFormat::Account),
+            ("receiver", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+        ],
+    },
+    CapDescriptor {
+        name: "marmalade.ledger.OFFER",
+        title: "Token Offer",
+        kind: CapKind::Labelled,
+        args: &[
+            ("token", CapArgFormat::TokenId),
+            ("seller", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+            ("timeout", CapArgFormat::TimeoutBlock),
+        ],
+    },
+    CapDescriptor {
+        name: "marmalade.ledger.SALE",
+        title: "Token Sale",
+        kind: CapKind::Labelled,
+        args: &[
+            ("token", CapArgFormat::TokenId),
+            ("seller", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+            ("timeout", CapArgFormat::TimeoutBlock),
+            ("sale", CapArgFormat::Id),
+        ],
+    },
+    CapDescriptor {
+        name: "marmalade.ledger.BUY",
+        title: "Token Buy",
+        kind: CapKind::Labelled,
+        args: &[
+            ("token", CapArgFormat::TokenId),
+            ("seller", CapArgFormat::Account),
+            ("buyer", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+            ("timeout", CapArgFormat::TimeoutBlock),
+            ("sale", CapArgFormat::Id),
+        ],
+    },
+    CapDescriptor {
+        name: "marmalade-v2.ledger.TRANSFER",
+        title: "Token Transfer",
+        kind: CapKind::Labelled,
+        args: &[
+            ("token", CapArgFormat::TokenId),
+            ("sender", CapArgFormat::Account),
+            ("receiver", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+        ],
+    },
+    CapDescriptor {
+        name: "marmalade-v2.ledger.OFFER",
+        title: "Token Offer",
+        kind: CapKind::Labelled,
+        args: &[
+            ("token", CapArgFormat::TokenId),
+            ("seller", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+            ("timeout", CapArgFormat::Timeout),
+        ],
+    },
+    CapDescriptor {
+        name: "marmalade-v2.ledger.SALE",
+        title: "Token Sale",
+        kind: CapKind::Labelled,
+        args: &[
+            ("token", CapArgFormat::TokenId),
+            ("seller", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+            ("timeout", CapArgFormat::Timeout),
+            ("sale", CapArgFormat::Id),
+        ],
+    },
+    CapDescriptor {
+        name: "marmalade-v2.ledger.BUY",
+        title: "Token Buy",
+        kind: CapKind::Labelled,
+        args: &[
+            ("token", CapArgFormat::TokenId),
+            ("seller", CapArgFormat::Account),
+            ("buyer", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+            ("sale", CapArgFormat::Id),
+        ],
+    },
+    // Any other fungible-v2 token, which must be in a namespace
+    CapDescriptor {
+        name: "*.*.TRANSFER",
+        title: "Transfer",
+        kind: CapKind::Transfer,
+        args: &[
+            ("sender", CapArgFormat::Account),
+            ("receiver", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+        ],
+    },
+    CapDescriptor {
+        name: "*.*.TRANSFER_XCHAIN",
+        title: "Transfer",
+        kind: CapKind::TransferXChain,
+        args: &[
+            ("sender", CapArgFormat::Account),
+            ("receiver", CapArgFormat::Account),
+            ("amount", CapArgFormat::Amount),
+            ("target chain", CapArgFormat::Chain),
+        ],
+    },
+];
+
+fn cap_name_matches(pattern: &str, name: &str) -> bool {
+    let mut pattern = pattern.split('.');
+    let mut name = name.split('.');
+    loop {
+        match (pattern.next(), name.next()) {
+            (None, None) => return true,
+            (Some("*"), Some(n)) if !n.is_empty() => {}
+            (Some(p), Some(n)) if p == n => {}
+            _ => return false,
+        }
+    }
+}
+
+// Split the rendered JSON of the args, as returned by KadenaCapabilityArgsInterp
+fn split_cap_args<'a>(
+    args: &'a [u8],
+    arg_lengths: &ArgListIndicesT,
+) -> Option<ArrayVec<&'a str, MAX_ARG_COUNT>> {
+    let mut split = ArrayVec::new();
+    let mut start = 0;
+    for end in arg_lengths.iter().take_while(|end| **end != 0) {
+        split.push(from_utf8(args.get(start..*end)?).ok()?);
+        start = *end;
+    }
+    split.push(from_utf8(args.get(start..)?).ok()?);
+    Some(split)
+}
+
+fn json_string(arg: &str) -> Option<&str> {
+    arg.strip_prefix('"')?.strip_suffix('"')
+}
+
+// An integer argument is either a JSON number, or a Pact integer as {"int":1} or {"int":"1"}
+fn parse_cap_integer(arg: &str) -> Option<u64> {
+    let int = match arg
+        .strip_prefix("{\"int\":")
+        .and_then(|a| a.strip_suffix('}'))
+    {
+        Some(int) => json_string(int).unwrap_or(int),
+        None => arg,
+    };
+    check_positive_integer(int)?;
+    int.parse().ok()
+}
+
+// Kadena's mainnet launched on 2019-10-30; an earlier timeout is likely a block height
+const MAINNET_LAUNCH_TIME: u64 = 1572393600;
+
+// A block height this large is likely a Unix time
+const MAX_TIMEOUT_BLOCK: u64 = 1_000_000_000;
+
+// Whether the timeouts of the arguments are plausible, so that the sale does not expire at once
+// or never
+fn cap_timeouts_valid(labels: &[(&str, CapArgFormat)], args: &[&str]) -> bool {
+    labels
+        .iter()
+        .zip(args)
+        .all(|((_, format), arg)| match format {
+            CapArgFormat::Timeout => {
+                matches!(parse_cap_integer(arg), Some(t) if t == 0 || t >= MAINNET_LAUNCH_TIME)
+            }
+            CapArgFormat::TimeoutBlock => {
+                matches!(parse_cap_integer(arg), Some(h) if h > 0 && h < MAX_TIMEOUT_BLOCK)
+            }
+            _ => true,
+        })
+}
+
+// The earliest Unix time at which one of the arguments times out, if any
+fn cap_earliest_timeout(labels: &[(&str, CapArgFormat)], args: &[&str]) -> Option<u64> {
+    labels
+        .iter()
+        .zip(args)
+        .filter_map(|((_, format), arg)| match format {
+            CapArgFormat::Timeout => parse_cap_integer(arg).filter(|t| *t > 0),
+            _ => None,
+        })
+        .min()
+}
+
+fn write_labelled_args<W: Write>(
+    w: &mut W,
+    labels: &[(&str, CapArgFormat)],
+    args: &[&str],
+) -> Result<(), ScrollerError> {
+    for (i, ((label, format), arg)) in labels.iter().zip(args).enumerate() {
+        if i > 0 {
+            write!(w, ", ")?;
+        }
+        if !label.is_empty() {
+            write!(w, "{}: ", label)?;
+        }
+        if expert_mode() {
+            write!(w, "{}", arg)?;
+            continue;
+        }
+        match format {
+            CapArgFormat::Account | CapArgFormat::Chain | CapArgFormat::Id => {
+                match json_string(arg) {
+                    Some(s) if can_show_unescaped(s.as_bytes()) => {
+                        write_unescaped(w, s.as_bytes())?
+                    }
+                    _ => write!(w, "{}", arg)?,
+                }
+            }
+            CapArgFormat::TokenId => match json_string(arg) {
+                Some(s)
+                    if s.len() > 24 && s.bytes().all(|c| c.is_ascii_graphic() && c != b'\\') =>
+                {
+                    write!(w, "{}...{}", &s[..10], &s[s.len() - 8..])?
+                }
+                Some(s) if can_show_unescaped(s.as_bytes()) => write_unescaped(w, s.as_bytes())?,
+                _ => write!(w, "{}", arg)?,
+            },
+            CapArgFormat::Amount => write_cap_amount(w, arg)?,
+            CapArgFormat::Timeout => match parse_cap_integer(arg) {
+                Some(0) => write!(w, "none")?,
+                Some(t) => write_utc_date(w, t)?,
+                None => write!(w, "{}", arg)?,
+            },
+            CapArgFormat::TimeoutBlock => match parse_cap_integer(arg) {
+                Some(height) => write!(w, "block {}", height)?,
+                None => write!(w, "{}", arg)?,
+            },
+            CapArgFormat::Json => write!(w, "{}", arg)?,
+        }
+    }
+    Ok(())
+}
+
+// Capability names are shown in full, with their namespace, eg n_<40 hex digits>.token.TRANSFER; a
+// longer name is shown as an unknown capability.
+const CAP_NAME_SIZE: usize = 96;
+
+type CapabilityInterpT = KadenaCapabilityInterp<
+    KadenaCapabilityArgsInterp,
+    Alt<JsonStringAccumulate<CAP_NAME_SIZE>, DropInterp>,
+>;
+type Capability = KadenaCapability<
+    Option<<KadenaCapabilityArgsInterp as ParserCommon<JsonArray<JsonAny>>>::Returning>,
+    Option<AltResult<ArrayVec<u8, CAP_NAME_SIZE>, ()>>,
+>;
+const CAPABILITY_INTERP: CapabilityInterpT = KadenaCapabilityInterp {
+    field_args: KadenaCapabilityArgsInterp,
+    field_name: Alt(JsonStringAccumulate::<CAP_NAME_SIZE>, DropInterp),
+};
+
+// Show a capability of the clist; counts are those of the capabilities before it, and the amount
+// of a transfer is added to the totals of the signer. The bool of the destination is whether the
+// capability was recognised.
+fn show_capability(
+    cap: &Capability,
+    counts: CapCountData,
+    totals: &mut TransferTotals,
+    destination: &mut Option<(CapCountData, bool)>,
+) -> Option<()> {
+    let mk_unknown_cap_title = || -> Option<_> {
+        let count = match counts {
+            CapCountData::CapCount { total_unknown, .. } => total_unknown,
+            _ => 0,
+        };
+        let mut buffer: ArrayString<22> = ArrayString::new();
+        write!(
+            mk_prompt_write(&mut buffer),
+            "Unknown Capability {}",
+            count + 1
+        )
+        .ok()?;
+        Some(buffer)
+    };
+    let mk_transfer_title = |title: &str| -> Option<_> {
+        let count = match counts {
+            CapCountData::CapCount {
+                total_transfers, ..
+            } => total_transfers,
+            _ => 0,
+        };
+        let mut buffer: ArrayString<22> = ArrayString::new();
+        write!(mk_prompt_write(&mut buffer), "{} {}", title, count + 1).ok()?;
+        Some(buffer)
+    };
+
+    trace!("Prompting for capability");
+    *destination = Some((CapCountData::IsUnknownCap, true));
+    let name = match cap.field_name.as_ref()? {
+        AltResult::First(name) => name.as_slice(),
+        AltResult::Second(_) => {
+            scroller(&mk_unknown_cap_title()?, |w| {
+                Ok(write!(w, "name cannot be displayed on Ledger")?)
+            })?;
+            set_from_thunk(destination, || Some((CapCountData::IsUnknownCap, false)));
+            return Some(());
+        }
+    };
+    let name_utf8 = from_utf8(name).ok()?;
+    let args = match cap.field_args.as_ref() {
+        Some((None, _)) => ArrayVec::new(),
+        Some((Some(Some(args)), arg_lengths)) => split_cap_args(args.as_slice(), arg_lengths)?,
+        _ => {
+            scroller(&mk_unknown_cap_title()?, |w| {
+                Ok(write!(
+                    w,
+                    "name: {}, args cannot be displayed on Ledger",
+                    name_utf8
+                )?)
+            })?;
+            set_from_thunk(destination, || Some((CapCountData::IsUnknownCap, false)));
+            // Fallback case
+            return Some(());
+        }
+    };
+    let descriptor = WELL_KNOWN_CAPS
+        .iter()
+        .find(|d| d.args.len() == args.len() && cap_name_matches(d.name, name_utf8));
+    match descriptor.map(|d| (d, d.kind)) {
+        Some((d, CapKind::Labelled)) => {
+            scroller(d.title, |w| {
+                if d.name.contains('*') {
+                    write!(w, "name: {}", name_utf8)?;
+                    if !args.is_empty() {
+                        write!(w, ", ")?;
+                    }
+                } else if args.is_empty() {
+                    write!(w, " ")?;
+                }
+                write_labelled_args(w, d.args, &args)
+            })?;
+            if !cap_timeouts_valid(d.args, &args) {
+                scroller("WARNING", |w| {
+                    Ok(write!(w, "The timeout of this capability is not a valid time or block height. The sale may expire at once or never.")?)
+                })?;
+            }
+            *destination = Some((
+                match cap_earliest_timeout(d.args, &args) {
+                    Some(t) => CapCountData::IsTimedCap(t),
+                    None => Summable::zero(),
+                },
+                true,
+            ));
+        }
+        Some((d, CapKind::Transfer)) => {
+            let module = name_utf8.rsplit_once('.')?.0;
+            scroller(&mk_transfer_title(d.title)?, |w| {
+                write_cap_amount(w, args[2])?;
+                if d.name.contains('*') {
+                    write!(w, " {}", module)?;
+                }
+                Ok(write!(w, " from {} to {}", args[0], args[1])?)
+            })?;
+            if !cap_amount_fits_precision(module, args[2]) {
+                show_precision_warning(module)?;
+            }
+            let transfer_caps = transfer_cap_summary(module, args[0], args[1], args[2], None);
+            totals.add_transfer(module, args[0], args[1], args[2], None);
+            *destination = Some((CapCountData::IsTransfer(transfer_caps), true));
+        }
+        Some((d, CapKind::TransferXChain)) => {
+            let module = name_utf8.rsplit_once('.')?.0;
+            scroller(&mk_transfer_title(d.title)?, |w| {
+                write!(w, "Cross-chain ")?;
+                write_cap_amount(w, args[2])?;
+                if d.name.contains('*') {
+                    write!(w, " {}", module)?;
+                }
+                Ok(write!(
+                    w,
+                    " from {} to {} to chain {}",
+                    args[0], args[1], args[3]
+                )?)
+            })?;
+            if !cap_amount_fits_precision(module, args[2]) {
+                show_precision_warning(module)?;
+            }
+            let transfer_caps =
+                transfer_cap_summary(module, args[0], args[1], args[2], Some(args[3]));
+            totals.add_transfer(module, args[0], args[1], args[2], Some(args[3]));
+            *destination = Some((CapCountData::IsTransfer(transfer_caps), true));
+        }
+        None => {
+            scroller(&mk_unknown_cap_title()?, |w| {
+                write!(w, "name: {}", name_utf8)?;
+                if args.is_empty() {
+                    write!(w, ", no args")?;
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    write!(w, ", arg {}: {}", i + 1, arg)?;
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Some(())
+}
+
+// The summary of the clist of a signer
+pub struct ClistSummary {
+    counts: CapCountData,
+    // Whether all the capabilities were recognised
+    all: bool,
+    totals: TransferTotals,
+}
+
+impl ClistSummary {
+    fn new() -> ClistSummary {
+        ClistSummary {
+            counts: Summable::zero(),
+            all: true,
+            totals: Summable::zero(),
+        }
+    }
+}
+
+// The clist of a signer, with each capability shown as soon as it is parsed. The counts are folded
+// as the capabilities come, and the transfer amounts are summed in place into the summary, so that
+// the totals are not copied for each capability.
+pub struct ClistInterp;
+
+pub enum ClistState {
+    Start,
+    FirstCap,
+    Cap(<CapabilityInterpT as ParserCommon<KadenaCapabilitySchema>>::State),
+    CapSep,
+}
+
+impl ParserCommon<JsonArray<KadenaCapabilitySchema>> for ClistInterp {
+    type State = (ClistState, Option<Capability>, ClistSummary);
+    type Returning = ClistSummary;
+    fn init(&self) -> Self::State {
+        (ClistState::Start, None, ClistSummary::new())
+    }
+}
+
+impl JsonInterp<JsonArray<KadenaCapabilitySchema>> for ClistInterp {
+    #[inline(never)]
+    fn parse(
+        &self,
+        (state, cap, summary): &mut Self::State,
+        token: JsonToken<'_>,
+        destination: &mut Option<Self::Returning>,
+    ) -> Result<(), Option<OOB>> {
+        loop {
+            use ClistState::*;
+            match state {
+                Start if token == JsonToken::BeginArray => set_from_thunk(state, || FirstCap),
+                FirstCap | CapSep if token == JsonToken::EndArray => {
+                    *destination = Some(core::mem::replace(summary, ClistSummary::new()));
+                    return Ok(());
+                }
+                FirstCap => {
+                    set_from_thunk(state, || {
+                        Cap(<CapabilityInterpT as ParserCommon<
+                            KadenaCapabilitySchema,
+                        >>::init(&CAPABILITY_INTERP))
+                    });
+                    continue;
                 }
-                Some((Some(Some(args)), arg_lengths)) => {
-                    if arg_lengths[3] != 0 {
-                        scroller(&mk_unknown_cap_title()?, |w| {
-                            Ok(write!(
-                                w,
-                                "name: {}, arg 1: {}, arg 2: {}, arg 3: {}, arg 4: {}, arg 5: {}",
-                                name_utf8,
-                                mkstr(args.as_slice().get(0..arg_lengths[0]))?,
-                                mkstr(args.as_slice().get(arg_lengths[0]..arg_lengths[1]))?,
-                                mkstr(args.as_slice().get(arg_lengths[1]..arg_lengths[2]))?,
-                                mkstr(args.as_slice().get(arg_lengths[2]..arg_lengths[3]))?,
-                                mkstr(args.as_slice().get(arg_lengths[3]..args.len()))?
-                            )?)
-                        })?;
-                    } else if arg_lengths[2] != 0 {
-                        if name == b"coin.TRANSFER_XCHAIN" {
-                            scroller(&mk_transfer_title()?, |w| {
-                                Ok(write!(
-                                    w,
-                                    "Cross-chain {} from {} to {} to chain {}",
-                                    mkstr(args.as_slice().get(arg_lengths[1]..arg_lengths[2]))?,
-                                    mkstr(args.as_slice().get(0..arg_lengths[0]))?,
-                                    mkstr(args.as_slice().get(arg_lengths[0]..arg_lengths[1]))?,
-                                    mkstr(args.as_slice().get(arg_lengths[2]..args.len()))?
-                                )?)
-                            })?;
-                            *destination = Some((CapCountData::IsTransfer, true));
-                        } else {
-                            scroller(&mk_unknown_cap_title()?, |w| {
-                                Ok(write!(
-                                    w,
-                                    "name: {}, arg 1: {}, arg 2: {}, arg 3: {}, arg 4: {}",
-                                    name_utf8,
-                                    mkstr(args.as_slice().get(0..arg_lengths[0]))?,
-                                    mkstr(args.as_slice().get(arg_lengths[0]..arg_lengths[1]))?,
-                                    mkstr(args.as_slice().get(arg_lengths[1]..arg_lengths[2]))?,
-                                    mkstr(args.as_slice().get(arg_lengths[2]..args.len()))?
-                                )?)
-                            })?;
-                        }
-                    } else if arg_lengths[1] != 0 {
-                        if name == b"coin.TRANSFER" {
-                            scroller(&mk_transfer_title()?, |w| {
-                                Ok(write!(
-                                    w,
-                                    "{} from {} to {}",
-                                    mkstr(args.as_slice().get(arg_lengths[1]..args.len()))?,
-                                    mkstr(args.as_slice().get(0..arg_lengths[0]))?,
-                                    mkstr(args.as_slice().get(arg_lengths[0]..arg_lengths[1]))?
-                                )?)
-                            })?;
-                            *destination = Some((CapCountData::IsTransfer, true));
-                        } else {
-                            scroller(&mk_unknown_cap_title()?, |w| {
-                                Ok(write!(
-                                    w,
-                                    "name: {}, arg 1: {}, arg 2: {}, arg 3: {}",
-                                    name_utf8,
-                                    mkstr(args.as_slice().get(0..arg_lengths[0]))?,
-                                    mkstr(args.as_slice().get(arg_lengths[0]..arg_lengths[1]))?,
-                                    mkstr(args.as_slice().get(arg_lengths[1]..args.len()))?
-                                )?)
-                            })?;
-                        }
-                    } else if arg_lengths[0] != 0 {
-                        scroller(&mk_unknown_cap_title()?, |w| {
-                            Ok(write!(
-                                w,
-                                "name: {}, arg 1: {}, arg 2: {}",
-                                name_utf8,
-                                mkstr(args.as_slice().get(0..arg_lengths[0]))?,
-                                mkstr(args.as_slice().get(arg_lengths[0]..args.len()))?
-                            )?)
-                        })?;
-                    } else if name == b"coin.ROTATE" {
-                        scroller("Rotate for account", |w| {
-                            Ok(write!(w, "{}", from_utf8(args.as_slice())?)?)
-                        })?;
-                        *destination = Some((Summable::zero(), true));
-                    } else {
-                        scroller(&mk_unknown_cap_title()?, |w| {
-                            Ok(write!(
-                                w,
-                                "name: {}, arg 1: {}",
-                                name_utf8,
-                                from_utf8(args.as_slice())?
-                            )?)
-                        })?;
-                    }
+                Cap(ref mut s) => {
+                    <CapabilityInterpT as JsonInterp<KadenaCapabilitySchema>>::parse(
+                        &CAPABILITY_INTERP,
+                        s,
+                        token,
+                        cap,
+                    )?;
+                    let mut shown = None;
+                    show_capability(
+                        cap.as_ref().ok_or(Some(OOB::Reject))?,
+                        summary.counts,
+                        &mut summary.totals,
+                        &mut shown,
+                    )
+                    .ok_or(Some(OOB::Reject))?;
+                    let (counts, all) = shown.ok_or(Some(OOB::Reject))?;
+                    summary.counts.add_and_set(&counts);
+                    summary.all &= all;
+                    *cap = None;
+                    set_from_thunk(state, || CapSep);
                 }
-                _ => {
-                    scroller(&mk_unknown_cap_title()?, |w| {
-                        Ok(write!(
-                            w,
-                            "name: {}, args cannot be displayed on Ledger",
-                            name_utf8
-                        )?)
-                    })?;
-                    set_from_thunk(destination, || Some((CapCountData::IsUnknownCap, false)));
-                    // Fallback case
+                CapSep if token == JsonToken::ValueSeparator => {
+                    set_from_thunk(state, || {
+                        Cap(<CapabilityInterpT as ParserCommon<
+                            KadenaCapabilitySchema,
+                        >>::init(&CAPABILITY_INTERP))
+                    });
                 }
+                _ => return Err(Some(OOB::Reject)),
             }
-            Some(())
-        },
-    ),
-));
+            break Err(None);
+        }
+    }
+}
 
-pub type SignHashImplT = impl InterpParser<SignHashParameters, Returning = ArrayVec<u8, 128_usize>>;
+pub type SignHashImplT = impl InterpParser<SignHashParameters, Returning = ApduResult<128>>;
 
 pub static SIGN_HASH_IMPL: SignHashImplT = Action(
     Preaction(
@@ -504,9 +2669,9 @@ pub static SIGN_HASH_IMPL: SignHashImplT = Action(
                 SubInterp(DefaultInterp),
                 // Ask the user if they accept the transaction body's hash
                 mkfn(|hash_val: &[u8; 32], destination: &mut Option<[u8; 32]>| {
-                    let the_hash = Base64Hash(*hash_val);
-                    scroller("Transaction hash", |w| Ok(write!(w, "{}", the_hash)?))?;
-                    *destination = Some(the_hash.0);
+                    // The hash is all there is to review, so it is always shown
+                    show_hash("Transaction hash", hash_val, true)?;
+                    *destination = Some(*hash_val);
                     Some(())
                 }),
             ),
@@ -514,17 +2679,18 @@ pub static SIGN_HASH_IMPL: SignHashImplT = Action(
                 SubInterp(DefaultInterp),
                 // And ask the user if this is the key the meant to sign with:
                 mkmvfn(
-                    |path: ArrayVec<u32, 10>, destination: &mut Option<ArrayVec<u32, 10>>| {
-                        if !path.starts_with(&BIP32_PREFIX[0..2]) {
-                            return None;
+                    |path: ArrayVec<u32, 10>,
+                     destination: &mut Option<Result<ArrayVec<u32, 10>, KadenaError>>| {
+                        let path = check_path(path);
+                        if let Ok(ref path) = path {
+                            with_public_keys(path, false, |_, pkh: &PKH| {
+                                try_option(|| -> Option<()> {
+                                    scroller("Sign for Address", |w| Ok(write!(w, "{}", pkh)?))?;
+                                    Some(())
+                                }())
+                            })
+                            .ok()?;
                         }
-                        with_public_keys(&path, false, |_, pkh: &PKH| {
-                            try_option(|| -> Option<()> {
-                                scroller("Sign for Address", |w| Ok(write!(w, "{}", pkh)?))?;
-                                Some(())
-                            }())
-                        })
-                        .ok()?;
                         *destination = Some(path);
                         Some(())
                     },
@@ -533,15 +2699,25 @@ pub static SIGN_HASH_IMPL: SignHashImplT = Action(
         ),
     ),
     mkfn(
-        |(hash, path): &(Option<[u8; 32]>, Option<ArrayVec<u32, 10>>), destination: &mut _| {
+        |(hash, path): &(
+            Option<[u8; 32]>,
+            Option<Result<ArrayVec<u32, 10>, KadenaError>>,
+        ),
+         destination: &mut Option<ApduResult<128>>| {
+            let path = match path.as_ref()? {
+                Ok(path) => path,
+                Err(e) => return reject(destination, *e),
+            };
             #[allow(clippy::needless_borrow)] // Needed for nanos
-            final_accept_prompt(&[&"Sign Transaction Hash?"])?;
+            if final_accept_prompt(&[&"Sign Transaction Hash?"]).is_none() {
+                return reject(destination, KadenaError::UserRejected);
+            }
 
             // By the time we get here, we've approved and just need to do the signature.
-            let sig = eddsa_sign(path.as_ref()?, false, &hash.as_ref()?[..]).ok()?;
+            let sig = eddsa_sign(path, false, &hash.as_ref()?[..]).ok()?;
             let mut rv = ArrayVec::<u8, 128>::new();
             rv.try_extend_from_slice(&sig.0[..]).ok()?;
-            *destination = Some(rv);
+            *destination = Some(Ok(rv));
             Some(())
         },
     ),
@@ -552,10 +2728,10 @@ pub struct KadenaCapabilityArgsInterp;
 // The Caps list is parsed and the args are stored in a single common ArrayVec of this size.
 // (This may be as large as the stack allows)
 #[cfg(target_os = "nanos")]
-const ARG_ARRAY_SIZE: usize = 184;
+pub const ARG_ARRAY_SIZE: usize = 184;
 #[cfg(not(target_os = "nanos"))]
-const ARG_ARRAY_SIZE: usize = 2048;
-const MAX_ARG_COUNT: usize = 5;
+pub const ARG_ARRAY_SIZE: usize = 2048;
+const MAX_ARG_COUNT: usize = 6;
 
 // Since we use a single ArrayVec to store the rendered json of all the args.
 // This list keeps track of the indices in the array for each arg, and even the args count
@@ -606,7 +2782,7 @@ impl JsonInterp<JsonArray<JsonAny>> for KadenaCapabilityArgsInterp {
             use KadenaCapabilityArgsInterpState::*;
             match state {
                 Start if token == JsonToken::BeginArray => {
-                    set_from_thunk(destination, || Some((None, [0, 0, 0, 0])));
+                    set_from_thunk(destination, || Some((None, [0; MAX_ARG_COUNT - 1])));
                     set_from_thunk(state, || Begin);
                 }
                 Begin if token == JsonToken::EndArray => {
@@ -680,9 +2856,7 @@ impl JsonInterp<JsonArray<JsonAny>> for KadenaCapabilityArgsInterp {
 #[allow(clippy::too_many_arguments)]
 #[inline(never)]
 fn handle_tx_param_1(
-    pkh_str: &ArrayString<64>,
-    hasher: &mut Blake2b,
-    tx_type: u8,
+    context: &mut MakeTxContext,
     recipient: &ArrayVec<u8, PARAM_RECIPIENT_SIZE>,
     recipient_chain: &ArrayVec<u8, PARAM_RECIPIENT_CHAIN_SIZE>,
     amount: &ArrayVec<u8, PARAM_AMOUNT_SIZE>,
@@ -690,6 +2864,16 @@ fn handle_tx_param_1(
     namespace: &ArrayVec<u8, PARAM_NAMESPACE_SIZE>,
     mod_name: &ArrayVec<u8, PARAM_MOD_NAME_SIZE>,
 ) -> Option<()> {
+    let MakeTxContext {
+        ref mut hasher,
+        ref pkh_str,
+        tx_type,
+        ref sender,
+        recipient_kind,
+        ref guard,
+        ref gas_payer,
+        ..
+    } = *context;
     let amount_str = from_utf8(amount).ok()?;
     let recipient_str = from_utf8(recipient).ok()?;
     let recipient_chain_str = from_utf8(recipient_chain).ok()?;
@@ -700,19 +2884,77 @@ fn handle_tx_param_1(
         return None;
     }
 
-    // recipient_str should be hex
-    if recipient_str.len() != 64 {
-        return None;
-    }
-    for (_, c) in recipient_str.char_indices() {
-        if !c.is_ascii_hexdigit() {
-            return None;
+    match recipient_kind {
+        RecipientKind::KAccount => {
+            // recipient_str should be hex
+            if recipient_str.len() != 64 {
+                return None;
+            }
+            for (_, c) in recipient_str.char_indices() {
+                if !c.is_ascii_hexdigit() {
+                    return None;
+                }
+            }
+        }
+        RecipientKind::WAccount => {
+            let (keys, pred) = guard.as_ref()?;
+            check_w_account(recipient_str, keys, pred)?;
+        }
+        RecipientKind::Named => {
+            check_account_name(recipient_str)?;
+            // Principal accounts are given with their own kind, so that their guard is checked
+            if recipient_str.starts_with("k:") || recipient_str.starts_with("w:") {
+                return None;
+            }
         }
     }
+    // Only k: recipients are given without the prefix of their account
+    let recipient_prefix = match recipient_kind {
+        RecipientKind::KAccount => "k:",
+        _ => "",
+    };
+    let write_keyset = |hasher: &mut CommandHasher| -> Option<()> {
+        match guard {
+            Some((keys, pred)) => {
+                write!(
+                    hasher,
+                    "\"ks\":{{\"pred\":\"{}\",\"keys\":[",
+                    from_utf8(pred).ok()?
+                )
+                .ok()?;
+                for (i, key) in keys.keys.iter().enumerate() {
+                    if i > 0 {
+                        write!(hasher, ",").ok()?;
+                    }
+                    write!(hasher, "\"").ok()?;
+                    for b in key {
+                        write!(hasher, "{:02x}", b).ok()?;
+                    }
+                    write!(hasher, "\"").ok()?;
+                }
+                write!(hasher, "]}}}}").ok()?;
+            }
+            None => {
+                write!(hasher, "\"ks\":{{\"pred\":\"keys-all\",\"keys\":[").ok()?;
+                write!(hasher, "\"{}\"]}}}}", recipient_str).ok()?;
+            }
+        }
+        Some(())
+    };
     check_positive_integer(recipient_chain_str)?;
-    check_decimal(amount_str)?;
+    let mut module: ArrayString<{ PARAM_NAMESPACE_SIZE + PARAM_MOD_NAME_SIZE + 1 }> =
+        ArrayString::new();
+    if namespace_str.is_empty() {
+        write!(module, "coin").ok()?;
+    } else {
+        write!(module, "{}.{}", namespace_str, mod_name_str).ok()?;
+    }
+    let amount = match token_precision(&module) {
+        Some(precision) => Decimal::parse_plain(amount_str)?.with_max_precision(precision)?,
+        None => Decimal::parse_plain(amount_str)?,
+    };
 
-    let coin_or_namespace = |hasher: &mut Blake2b| -> Option<()> {
+    let coin_or_namespace = |hasher: &mut CommandHasher| -> Option<()> {
         if namespace_str.is_empty() {
             write!(hasher, "coin").ok()?;
         } else {
@@ -725,7 +2967,7 @@ fn handle_tx_param_1(
     // The JSON struct begins here, and ends in handle_tx_params_2
     write!(hasher, "{{").ok()?;
     write!(hasher, "\"networkId\":\"{}\"", network_str).ok()?;
-    match tx_type {
+    match tx_type & TX_TYPE_MASK {
         0 => {
             write!(
                 hasher,
@@ -734,74 +2976,102 @@ fn handle_tx_param_1(
             .ok()?;
             coin_or_namespace(hasher)?;
             write!(hasher, ".transfer").ok()?;
-            write!(hasher, " \\\"k:{}\\\"", pkh_str).ok()?;
-            write!(hasher, " \\\"k:{}\\\"", recipient_str).ok()?;
+            write!(hasher, " \\\"{}\\\"", PactJsonEscaped(sender)).ok()?;
+            write!(
+                hasher,
+                " \\\"{}{}\\\"",
+                recipient_prefix,
+                PactJsonEscaped(recipient_str)
+            )
+            .ok()?;
             write!(hasher, " {})\"}}}}", amount_str).ok()?;
             write!(hasher, ",\"signers\":[{{\"pubKey\":").ok()?;
             write!(hasher, "\"{}\"", pkh_str).ok()?;
             write!(hasher, ",\"clist\":[{{\"args\":[").ok()?;
-            write!(hasher, "\"k:{}\",", pkh_str).ok()?;
-            write!(hasher, "\"k:{}\",", recipient_str).ok()?;
-            write!(hasher, "{}]", amount_str).ok()?;
-            write!(hasher, ",\"name\":\"").ok()?;
-            coin_or_namespace(hasher)?;
+            write!(hasher, "\"{}\",", JsonEscaped(sender)).ok()?;
             write!(
                 hasher,
-                ".TRANSFER\"}},{{\"args\":[],\"name\":\"coin.GAS\"}}]}}]"
+                "\"{}{}\",",
+                recipient_prefix,
+                JsonEscaped(recipient_str)
             )
             .ok()?;
+            write!(hasher, "{}]", amount_str).ok()?;
+            write!(hasher, ",\"name\":\"").ok()?;
+            coin_or_namespace(hasher)?;
+            write!(hasher, ".TRANSFER\"}},").ok()?;
+            write_gas_cap(hasher, sender, gas_payer)?;
+            write!(hasher, "]}}]").ok()?;
         }
         1 => {
             write!(hasher, ",\"payload\":{{\"exec\":{{\"data\":{{").ok()?;
-            write!(hasher, "\"ks\":{{\"pred\":\"keys-all\",\"keys\":[").ok()?;
-            write!(hasher, "\"{}\"]}}}}", recipient_str).ok()?;
+            write_keyset(hasher)?;
             write!(hasher, ",\"code\":\"(").ok()?;
             coin_or_namespace(hasher)?;
             write!(hasher, ".transfer-create").ok()?;
-            write!(hasher, " \\\"k:{}\\\"", pkh_str).ok()?;
-            write!(hasher, " \\\"k:{}\\\"", recipient_str).ok()?;
+            write!(hasher, " \\\"{}\\\"", PactJsonEscaped(sender)).ok()?;
+            write!(
+                hasher,
+                " \\\"{}{}\\\"",
+                recipient_prefix,
+                PactJsonEscaped(recipient_str)
+            )
+            .ok()?;
             write!(hasher, " (read-keyset \\\"ks\\\")").ok()?;
             write!(hasher, " {})\"}}}}", amount_str).ok()?;
             write!(hasher, ",\"signers\":[{{\"pubKey\":").ok()?;
             write!(hasher, "\"{}\"", pkh_str).ok()?;
             write!(hasher, ",\"clist\":[{{\"args\":[").ok()?;
-            write!(hasher, "\"k:{}\",", pkh_str).ok()?;
-            write!(hasher, "\"k:{}\",", recipient_str).ok()?;
-            write!(hasher, "{}]", amount_str).ok()?;
-            write!(hasher, ",\"name\":\"").ok()?;
-            coin_or_namespace(hasher)?;
+            write!(hasher, "\"{}\",", JsonEscaped(sender)).ok()?;
             write!(
                 hasher,
-                ".TRANSFER\"}},{{\"args\":[],\"name\":\"coin.GAS\"}}]}}]"
+                "\"{}{}\",",
+                recipient_prefix,
+                JsonEscaped(recipient_str)
             )
             .ok()?;
+            write!(hasher, "{}]", amount_str).ok()?;
+            write!(hasher, ",\"name\":\"").ok()?;
+            coin_or_namespace(hasher)?;
+            write!(hasher, ".TRANSFER\"}},").ok()?;
+            write_gas_cap(hasher, sender, gas_payer)?;
+            write!(hasher, "]}}]").ok()?;
         }
         2 => {
             write!(hasher, ",\"payload\":{{\"exec\":{{\"data\":{{").ok()?;
-            write!(hasher, "\"ks\":{{\"pred\":\"keys-all\",\"keys\":[").ok()?;
-            write!(hasher, "\"{}\"]}}}}", recipient_str).ok()?;
+            write_keyset(hasher)?;
             write!(hasher, ",\"code\":\"(").ok()?;
             coin_or_namespace(hasher)?;
             write!(hasher, ".transfer-crosschain").ok()?;
-            write!(hasher, " \\\"k:{}\\\"", pkh_str).ok()?;
-            write!(hasher, " \\\"k:{}\\\"", recipient_str).ok()?;
+            write!(hasher, " \\\"{}\\\"", PactJsonEscaped(sender)).ok()?;
+            write!(
+                hasher,
+                " \\\"{}{}\\\"",
+                recipient_prefix,
+                PactJsonEscaped(recipient_str)
+            )
+            .ok()?;
             write!(hasher, " (read-keyset \\\"ks\\\")").ok()?;
             write!(hasher, " \\\"{}\\\"", recipient_chain_str).ok()?;
             write!(hasher, " {})\"}}}}", amount_str).ok()?;
             write!(hasher, ",\"signers\":[{{\"pubKey\":").ok()?;
             write!(hasher, "\"{}\"", pkh_str).ok()?;
             write!(hasher, ",\"clist\":[{{\"args\":[").ok()?;
-            write!(hasher, "\"k:{}\",", pkh_str).ok()?;
-            write!(hasher, "\"k:{}\",", recipient_str).ok()?;
-            write!(hasher, "{},", amount_str).ok()?;
-            write!(hasher, "\"{}\"]", recipient_chain_str).ok()?;
-            write!(hasher, ",\"name\":\"").ok()?;
-            coin_or_namespace(hasher)?;
+            write!(hasher, "\"{}\",", JsonEscaped(sender)).ok()?;
             write!(
                 hasher,
-                ".TRANSFER_XCHAIN\"}},{{\"args\":[],\"name\":\"coin.GAS\"}}]}}]"
+                "\"{}{}\",",
+                recipient_prefix,
+                JsonEscaped(recipient_str)
             )
             .ok()?;
+            write!(hasher, "{},", amount_str).ok()?;
+            write!(hasher, "\"{}\"]", recipient_chain_str).ok()?;
+            write!(hasher, ",\"name\":\"").ok()?;
+            coin_or_namespace(hasher)?;
+            write!(hasher, ".TRANSFER_XCHAIN\"}},").ok()?;
+            write_gas_cap(hasher, sender, gas_payer)?;
+            write!(hasher, "]}}]").ok()?;
         }
         _ => {}
     }
@@ -814,23 +3084,96 @@ fn handle_tx_param_1(
         })?;
     }
 
-    scroller_paginated("From", |w| Ok(write!(w, "k:{}", pkh_str)?))?;
-    scroller_paginated("To", |w| Ok(write!(w, "k:{}", recipient_str)?))?;
-    if tx_type == 2 {
+    if tx_type & TX_FLAG_NAMED_SENDER != 0 {
+        scroller_paginated("From", |w| {
+            Ok(write!(w, "{} with key {}", sender, pkh_str)?)
+        })?;
+    } else {
+        scroller_paginated("From", |w| Ok(write!(w, "{}", sender)?))?;
+    }
+    scroller_paginated("To", |w| {
+        Ok(write!(w, "{}{}", recipient_prefix, recipient_str)?)
+    })?;
+    if let Some((keys, pred)) = guard {
+        show_keyset(None, keys, pred)?;
+    }
+    if tx_type & TX_TYPE_MASK == 2 {
         scroller("To Chain", |w| Ok(write!(w, "{}", recipient_chain_str)?))?;
     }
     if namespace_str.is_empty() {
-        scroller("Amount", |w| Ok(write!(w, "KDA {}", amount_str)?))?;
+        scroller("Amount", |w| Ok(write!(w, "KDA {}", amount)?))?;
     } else {
-        scroller("Amount", |w| Ok(write!(w, "{}", amount_str)?))?;
+        scroller("Amount", |w| Ok(write!(w, "{}", amount)?))?;
     }
     Some(())
 }
 
+fn write_gas_cap(
+    hasher: &mut CommandHasher,
+    sender: &str,
+    gas_payer: &Option<GasPayerAccount>,
+) -> Option<()> {
+    match gas_payer {
+        Some(payer) => write!(
+            hasher,
+            "{{\"args\":[\"{}\",{{\"int\":{}}},{}],\"name\":\"{}.GAS_PAYER\"}}",
+            JsonEscaped(sender),
+            payer.limit,
+            payer.price,
+            payer.module
+        )
+        .ok(),
+        None => write!(hasher, "{{\"args\":[],\"name\":\"coin.GAS\"}}").ok(),
+    }
+}
+
+// The JSON struct of a continuation begins here, and the proof is written by
+// ContinuationProofParser
+fn handle_cont_params(
+    context: &mut MakeTxContext,
+    network: &ArrayVec<u8, PARAM_NETWORK_SIZE>,
+    pact_id: &ArrayVec<u8, PARAM_PACT_ID_SIZE>,
+    step: &ArrayVec<u8, PARAM_STEP_SIZE>,
+    rollback: u8,
+) -> Option<()> {
+    let hasher = &mut context.hasher;
+    let network_str = from_utf8(network).ok()?;
+    let pact_id_str = from_utf8(pact_id).ok()?;
+    let step_str = from_utf8(step).ok()?;
+    // A pact id is an unpadded base64url hash
+    if pact_id_str.len() != PARAM_PACT_ID_SIZE || !pact_id.iter().all(|c| is_base64url(*c)) {
+        return None;
+    }
+    check_positive_integer(step_str)?;
+    let rollback = match rollback {
+        0 => false,
+        1 => true,
+        _ => return None,
+    };
+
+    write!(hasher, "{{").ok()?;
+    write!(hasher, "\"networkId\":\"{}\"", network_str).ok()?;
+    write!(hasher, ",\"payload\":{{\"cont\":{{").ok()?;
+    write!(hasher, "\"pactId\":\"{}\"", pact_id_str).ok()?;
+    write!(hasher, ",\"step\":{}", step_str).ok()?;
+    write!(hasher, ",\"rollback\":{}", rollback).ok()?;
+    write!(hasher, ",\"data\":{{}},\"proof\":").ok()?;
+
+    scroller_paginated("Continuing Pact", |w| Ok(write!(w, "{}", pact_id_str)?))?;
+    scroller("Step", |w| Ok(write!(w, "{}", step_str)?))?;
+    scroller("Rollback", |w| {
+        Ok(write!(w, "{}", if rollback { "Yes" } else { "No" })?)
+    })?;
+    Some(())
+}
+
+fn is_base64url(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'-' || c == b'_'
+}
+
 #[allow(clippy::too_many_arguments)]
 fn handle_tx_params_2(
-    pkh_str: &ArrayString<64>,
-    hasher: &mut Blake2b,
+    context: &mut MakeTxContext,
     gas_price: &ArrayVec<u8, PARAM_GAS_PRICE_SIZE>,
     gas_limit: &ArrayVec<u8, PARAM_GAS_LIMIT_SIZE>,
     creation_time: &ArrayVec<u8, PARAM_CREATION_TIME_SIZE>,
@@ -838,167 +3181,669 @@ fn handle_tx_params_2(
     nonce: &ArrayVec<u8, PARAM_NOONCE_SIZE>,
     ttl: &ArrayVec<u8, PARAM_TTL_SIZE>,
 ) -> Option<()> {
+    let MakeTxContext {
+        ref mut hasher,
+        tx_type,
+        ref sender,
+        ref gas_payer,
+        ..
+    } = *context;
     let gas_price_str = from_utf8(gas_price).ok()?;
     let gas_limit_str = from_utf8(gas_limit).ok()?;
     let chain_id_str = from_utf8(chain_id).ok()?;
     let ttl_str = from_utf8(ttl).ok()?;
     let creation_time_str = from_utf8(creation_time).ok()?;
-    {
-        // gas_price_str should be positive integer, decimal or exponential value
-        if gas_price_str.is_empty() {
-            return None;
-        }
-        let mut decimal = false;
-        let mut exp = false;
-        let mut should_be_minus = false;
-        for (_, c) in gas_price_str.char_indices() {
-            if should_be_minus {
-                if c == '-' {
-                    should_be_minus = false;
-                    continue;
-                } else {
-                    return None;
-                }
-            }
-            if !c.is_ascii_digit() {
-                if c == '.' && !decimal {
-                    decimal = true;
-                    continue;
-                }
-                if c == 'e' && !exp && decimal {
-                    exp = true;
-                    should_be_minus = true;
-                    continue;
-                }
-                return None;
-            }
-        }
-    }
+    // gas_price_str should be positive integer, decimal or exponential value
+    let gas_price_decimal = Decimal::parse_gas_price(gas_price_str)?;
     check_positive_integer(gas_limit_str)?;
     check_positive_integer(chain_id_str)?;
     check_positive_integer(creation_time_str)?;
-    check_decimal(ttl_str)?;
+    Decimal::parse_plain(ttl_str)?;
     write!(hasher, ",\"meta\":{{").ok()?;
     write!(hasher, "\"creationTime\":{}", creation_time_str).ok()?;
     write!(hasher, ",\"ttl\":{}", ttl_str).ok()?;
     write!(hasher, ",\"gasLimit\":{}", gas_limit_str).ok()?;
     write!(hasher, ",\"chainId\":\"{}\"", chain_id_str).ok()?;
     write!(hasher, ",\"gasPrice\":{}", gas_price_str).ok()?;
-    write!(hasher, ",\"sender\":\"k:{}\"", pkh_str).ok()?;
+    let gas_sender = match gas_payer {
+        Some(payer) => payer.account.as_str(),
+        None => sender.as_str(),
+    };
+    write!(hasher, ",\"sender\":\"{}\"", JsonEscaped(gas_sender)).ok()?;
     write!(hasher, "}}").ok()?;
     write!(hasher, ",\"nonce\":\"{}\"", from_utf8(nonce).ok()?).ok()?;
     // The JSON struct ends here
     write!(hasher, "}}").ok()?;
 
+    let is_continuation = tx_type & TX_TYPE_MASK == TX_TYPE_CONTINUATION;
+    if is_continuation {
+        scroller("Target Chain", |w| Ok(write!(w, "{}", chain_id_str)?))?;
+    }
+    match gas_payer {
+        Some(payer) => {
+            scroller_paginated("Gas paid by", |w| {
+                Ok(write!(
+                    w,
+                    "{} with {}.GAS_PAYER",
+                    payer.account, payer.module
+                )?)
+            })?;
+            if expert_mode() {
+                scroller("GAS_PAYER Limit", |w| Ok(write!(w, "{}", payer.limit)?))?;
+                scroller("GAS_PAYER Price", |w| Ok(write!(w, "{}", payer.price)?))?;
+            }
+        }
+        // The sender of a transfer is shown as "From"; a continuation has no other sender
+        None if is_continuation => {
+            scroller_paginated("Gas paid by", |w| Ok(write!(w, "{}", sender)?))?;
+        }
+        None => {}
+    }
     scroller("Gas Limit (1/2)", |w| {
         Ok(write!(w, "{} Max", from_utf8(gas_limit)?,)?)
     })?;
     scroller("Gas Price (2/2)", |w| {
-        Ok(write!(w, "KDA {}", from_utf8(gas_price)?)?)
+        Ok(write!(w, "KDA {}", gas_price_decimal)?)
     })?;
+    show_max_fee(gas_limit_str, gas_price_str)?;
+    if expert_mode() {
+        show_ttl(ttl)?;
+        show_creation_time(creation_time)?;
+        scroller("Nonce", |w| Ok(write!(w, "{}", from_utf8(nonce)?)?))?;
+    }
     Some(())
 }
 
-fn check_decimal(s: &str) -> Option<()> {
+// Fees above this many KDA are flagged to the user
+const HIGH_FEE_THRESHOLD: Decimal = Decimal::from_integer(1);
+
+fn show_max_fee(gas_limit: &str, gas_price: &str) -> Option<()> {
+    let fee = Decimal::parse(gas_limit)
+        .zip(Decimal::parse(gas_price))
+        .and_then(|(limit, price)| limit.checked_mul(&price));
+    match fee {
+        Some(fee) => {
+            scroller("Max Fee", |w| Ok(write!(w, "KDA {}", fee)?))?;
+            if fee > HIGH_FEE_THRESHOLD {
+                scroller("WARNING", |w| {
+                    Ok(write!(
+                        w,
+                        "The maximum fee of this transaction is unusually high. Check the gas limit and gas price."
+                    )?)
+                })?;
+            }
+            Some(())
+        }
+        None => scroller("Max Fee", |w| {
+            Ok(write!(w, "cannot be computed on Ledger")?)
+        }),
+    }
+}
+
+fn check_positive_integer(s: &str) -> Option<()> {
     if s.is_empty() {
         return None;
     }
-    let mut decimal = false;
     for (_, c) in s.char_indices() {
         if !c.is_ascii_digit() {
-            if c == '.' && !decimal {
-                decimal = true;
-                continue;
+            return None;
+        }
+    }
+    Some(())
+}
+
+// Define some useful type aliases
+pub type OptionByteVec<const N: usize> = Option<ArrayVec<u8, N>>;
+type SubDefT = SubInterp<DefaultInterp>;
+const SUB_DEF: SubDefT = SubInterp(DefaultInterp);
+
+// A string parameter of MAKE_TRANSFER_TX; one which is too long for its buffer is skipped and
+// returned as FieldTooLong, rather than rejected as invalid input
+pub struct ParamInterp;
+
+pub enum ParamState<const N: usize> {
+    Length,
+    // The value is None when it does not fit
+    Bytes {
+        remaining: u8,
+        value: Option<ArrayVec<u8, N>>,
+    },
+}
+
+impl<const N: usize> ParserCommon<ByteDArray<N>> for ParamInterp {
+    type State = ParamState<N>;
+    type Returning = Result<ArrayVec<u8, N>, KadenaError>;
+    fn init(&self) -> Self::State {
+        ParamState::Length
+    }
+}
+
+impl<const N: usize> InterpParser<ByteDArray<N>> for ParamInterp {
+    #[inline(never)]
+    fn parse<'a>(
+        &self,
+        state: &mut Self::State,
+        chunk: &'a [u8],
+        destination: &mut Option<Self::Returning>,
+    ) -> ParseResult<'a> {
+        let mut cursor = chunk;
+        loop {
+            match state {
+                ParamState::Length => {
+                    let (length, rest) = cursor.split_first().ok_or((None, cursor))?;
+                    cursor = rest;
+                    *state = ParamState::Bytes {
+                        remaining: *length,
+                        value: (*length as usize <= N).then(ArrayVec::new),
+                    };
+                }
+                ParamState::Bytes {
+                    ref mut remaining,
+                    ref mut value,
+                } if *remaining > 0 => {
+                    if cursor.is_empty() {
+                        break Err((None, cursor));
+                    }
+                    let (bytes, rest) = cursor.split_at(cursor.len().min(*remaining as usize));
+                    if let Some(value) = value {
+                        value
+                            .try_extend_from_slice(bytes)
+                            .or(Err((Some(OOB::Reject), cursor)))?;
+                    }
+                    *remaining -= bytes.len() as u8;
+                    cursor = rest;
+                }
+                ParamState::Bytes { ref mut value, .. } => {
+                    *destination = Some(value.take().ok_or(KadenaError::FieldTooLong));
+                    break Ok(cursor);
+                }
             }
+        }
+    }
+}
+
+// Hashes the command as it is written, and counts its length so that it can be returned with
+// the hash
+pub struct CommandHasher {
+    hasher: Blake2b,
+    len: u32,
+}
+
+impl CommandHasher {
+    fn new() -> Self {
+        CommandHasher {
+            hasher: Hasher::new(),
+            len: 0,
+        }
+    }
+}
+
+impl Write for CommandHasher {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.len = u32::try_from(s.len())
+            .ok()
+            .and_then(|n| self.len.checked_add(n))
+            .ok_or(core::fmt::Error)?;
+        self.hasher.write_str(s)
+    }
+}
+
+// This is kept in State to avoid passing it in-between the sub-parsers
+// via parameters / DynBind
+pub struct MakeTxContext {
+    hasher: CommandHasher,
+    privkey: ECPrivateKey<32, 'E'>,
+    pkh_str: ArrayString<64>,
+    tx_type: u8,
+    // The account sending the transfer and paying for gas; the k: account of the key unless a
+    // named sender is given
+    sender: ArrayString<PARAM_SENDER_SIZE>,
+    recipient_kind: RecipientKind,
+    // The guard of a w: or named recipient, when it is given
+    guard: Option<(KeysetKeys, ArrayVec<u8, PARAM_GUARD_PRED_SIZE>)>,
+    gas_payer: Option<GasPayerAccount>,
+    // Set when a parameter is too long, to reject the transaction with FieldTooLong
+    error: Option<KadenaError>,
+}
+
+// An account paying for gas through the GAS_PAYER capability of a gas station module, eg
+// util.gas-guards, with the arguments of the capability other than the user
+pub struct GasPayerAccount {
+    account: ArrayString<PARAM_SENDER_SIZE>,
+    module: ArrayString<PARAM_GAS_PAYER_MODULE_SIZE>,
+    limit: ArrayString<PARAM_GAS_LIMIT_SIZE>,
+    price: ArrayString<PARAM_GAS_PRICE_SIZE>,
+}
+
+impl MakeTxContext {
+    // The guard of a named account is only part of the transaction when the account is created
+    fn has_guard(&self) -> bool {
+        match self.recipient_kind {
+            RecipientKind::KAccount => false,
+            RecipientKind::WAccount => true,
+            RecipientKind::Named => self.tx_type & TX_TYPE_MASK != 0,
+        }
+    }
+
+    // The value of a parameter, or None when it is too long, recording why
+    fn param<const N: usize>(
+        &mut self,
+        param: Result<ArrayVec<u8, N>, KadenaError>,
+    ) -> Option<ArrayVec<u8, N>> {
+        param.map_err(|e| self.error = Some(e)).ok()
+    }
+}
+
+impl MakeTxContext {
+    fn for_path(path: &ArrayVec<u32, 10>) -> Option<MakeTxContext> {
+        let privkey = Ed25519::derive_from_path(path);
+        let mut pkh_str: ArrayString<64> = ArrayString::new();
+        with_public_keys_int(&privkey, |_: &_, pkh: &PKH| {
+            try_option(write!(mk_prompt_write(&mut pkh_str), "{}", pkh).ok())
+        })
+        .ok()?;
+        let mut sender = ArrayString::new();
+        write!(sender, "k:{}", pkh_str).ok()?;
+        Some(MakeTxContext {
+            hasher: CommandHasher::new(),
+            privkey,
+            pkh_str,
+            tx_type: 0,
+            sender,
+            recipient_kind: RecipientKind::KAccount,
+            guard: None,
+            gas_payer: None,
+            error: None,
+        })
+    }
+}
+
+pub type TxTypeParserT = impl InterpParser<Byte, Returning = MakeTxContext>;
+
+const TX_TYPE_PARSER: TxTypeParserT = MoveAction(
+    DefaultInterp,
+    mkmvfn(|tx_type: u8, destination: &mut Option<MakeTxContext>| {
+        if tx_type & !(TX_TYPE_MASK | TX_FLAGS_SUPPORTED) != 0
+            || tx_type & TX_TYPE_MASK > TX_TYPE_CONTINUATION
+        {
+            return None;
+        }
+        // A continuation has no recipient
+        if tx_type & TX_TYPE_MASK == TX_TYPE_CONTINUATION && tx_type & TX_FLAG_RECIPIENT_KIND != 0 {
             return None;
         }
+        destination.as_mut()?.tx_type = tx_type;
+        Some(())
+    }),
+);
+
+// Kadena account names are 3 to 256 characters; they are limited here to printable ASCII, so that
+// they are shown as they are signed
+fn check_account_name(account: &str) -> Option<()> {
+    if account.len() < 3 || !account.bytes().all(|c| (0x20..0x7f).contains(&c)) {
+        return None;
     }
     Some(())
 }
 
-fn check_positive_integer(s: &str) -> Option<()> {
-    if s.is_empty() {
+// An account name written into a JSON string, with its quotes and backslashes escaped
+struct JsonEscaped<'a>(&'a str);
+
+impl core::fmt::Display for JsonEscaped<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for c in self.0.chars() {
+            if c == '"' || c == '\\' {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+// An account name written into a Pact string in the code of the command, which is itself a JSON
+// string, so its quotes and backslashes are escaped for Pact and then for JSON
+struct PactJsonEscaped<'a>(&'a str);
+
+impl core::fmt::Display for PactJsonEscaped<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for c in self.0.chars() {
+            if c == '"' || c == '\\' {
+                f.write_str("\\\\\\")?;
+            }
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+pub type SenderParserT =
+    impl InterpParser<ByteDArray<PARAM_SENDER_SIZE>, Returning = MakeTxContext>;
+
+const SENDER_PARSER: SenderParserT = MoveAction(
+    ParamInterp,
+    mkmvfn(
+        |sender: Result<ArrayVec<u8, PARAM_SENDER_SIZE>, KadenaError>,
+         destination: &mut Option<MakeTxContext>| {
+            let context = destination.as_mut()?;
+            let sender = context.param(sender)?;
+            let sender = from_utf8(&sender).ok()?;
+            check_account_name(sender)?;
+            // A k: account is only guarded by its own key
+            if let Some(key) = sender.strip_prefix("k:") {
+                if key != context.pkh_str.as_str() {
+                    return None;
+                }
+            }
+            context.sender = ArrayString::from(sender).ok()?;
+            Some(())
+        },
+    ),
+);
+
+pub type RecipientKindParserT = impl InterpParser<Byte, Returning = MakeTxContext>;
+
+const RECIPIENT_KIND_PARSER: RecipientKindParserT = MoveAction(
+    DefaultInterp,
+    mkmvfn(|kind: u8, destination: &mut Option<MakeTxContext>| {
+        destination.as_mut()?.recipient_kind = RecipientKind::try_from(kind).ok()?;
+        Some(())
+    }),
+);
+
+type GuardParserT = (ParamInterp, SubDefT);
+
+pub type RecipientGuardParserT = impl InterpParser<RecipientGuard, Returning = MakeTxContext>;
+
+const RECIPIENT_GUARD_PARSER: RecipientGuardParserT = MoveAction(
+    (ParamInterp, SUB_DEF),
+    mkmvfn(
+        |(pred, keys): <GuardParserT as ParserCommon<RecipientGuard>>::Returning,
+         destination: &mut Option<MakeTxContext>| {
+            let pred = destination.as_mut()?.param(pred?)?;
+            let keys = keys?;
+            match pred.as_slice() {
+                b"keys-all" | b"keys-any" if !keys.is_empty() => {}
+                b"keys-2" if keys.len() >= 2 => {}
+                _ => return None,
+            }
+            // A keyset is a set of keys, and the name of a w: account is made from them in order
+            if keys.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return None;
+            }
+            let mut guard_keys = KeysetKeys::zero();
+            for key in keys.iter() {
+                guard_keys.keys.try_push(*key).ok()?;
+            }
+            guard_keys.count = keys.len();
+            destination.as_mut()?.guard = Some((guard_keys, pred));
+            Some(())
+        },
+    ),
+);
+
+// A w: account is named after the hash of the keys of its guard, and its predicate
+fn check_w_account(account: &str, keys: &KeysetKeys, pred: &[u8]) -> Option<()> {
+    let (hash, account_pred) = account.strip_prefix("w:")?.split_once(':')?;
+    if account_pred.as_bytes() != pred {
         return None;
     }
-    for (_, c) in s.char_indices() {
-        if !c.is_ascii_digit() {
-            return None;
+    let mut hasher: Blake2b = Hasher::new();
+    for key in keys.keys.iter() {
+        for b in key {
+            write!(hasher, "{:02x}", b).ok()?;
         }
     }
+    let keys_hash: Base64Hash<32> = hasher.finalize();
+    let mut keys_hash_str: ArrayString<44> = ArrayString::new();
+    write!(keys_hash_str, "{}", keys_hash).ok()?;
+    if hash != keys_hash_str.as_str() {
+        return None;
+    }
     Some(())
 }
 
-// Define some useful type aliases
-pub type OptionByteVec<const N: usize> = Option<ArrayVec<u8, N>>;
-type SubDefT = SubInterp<DefaultInterp>;
-const SUB_DEF: SubDefT = SubInterp(DefaultInterp);
-
-// This is kept in State to avoid passing it in-between the sub-parsers
-// via parameters / DynBind
-type HasherAndPrivKey = (Blake2b, ECPrivateKey<32, 'E'>);
+type GasPayerParserT = (ParamInterp, (ParamInterp, (ParamInterp, ParamInterp)));
 
-pub type PathParserT = impl InterpParser<Bip32Key, Returning = HasherAndPrivKey>;
+pub type GasPayerAccountParserT = impl InterpParser<GasPayer, Returning = MakeTxContext>;
 
-const PATH_PARSER: PathParserT = MoveAction(
-    SUB_DEF,
+const GAS_PAYER_PARSER: GasPayerAccountParserT = MoveAction(
+    (ParamInterp, (ParamInterp, (ParamInterp, ParamInterp))),
     mkmvfn(
-        |path: <SubDefT as ParserCommon<Bip32Key>>::Returning,
-         destination: &mut Option<HasherAndPrivKey>| {
-            if !path.starts_with(&BIP32_PREFIX[0..2]) {
+        |(account, optv1): <GasPayerParserT as ParserCommon<GasPayer>>::Returning,
+         destination: &mut Option<MakeTxContext>| {
+            let context = destination.as_mut()?;
+            let (module, optv2) = optv1?;
+            let (limit, price) = optv2?;
+            let (account, module, limit, price) = (
+                context.param(account?),
+                context.param(module?),
+                context.param(limit?),
+                context.param(price?),
+            );
+            let account = from_utf8(account.as_ref()?).ok()?;
+            let module = from_utf8(module.as_ref()?).ok()?;
+            let limit = from_utf8(limit.as_ref()?).ok()?;
+            let price = from_utf8(price.as_ref()?).ok()?;
+            check_account_name(account)?;
+            // A k: account pays for gas with coin.GAS, signed with its own key
+            if account.starts_with("k:") {
                 return None;
             }
-            set_from_thunk(destination, || {
-                Some((Hasher::new(), Ed25519::derive_from_path(&path)))
+            check_module_name(module)?;
+            check_positive_integer(limit)?;
+            Decimal::parse_gas_price(price)?;
+            context.gas_payer = Some(GasPayerAccount {
+                account: ArrayString::from(account).ok()?,
+                module: ArrayString::from(module).ok()?,
+                limit: ArrayString::from(limit).ok()?,
+                price: ArrayString::from(price).ok()?,
             });
             Some(())
         },
     ),
 );
 
+// A qualified module name, eg free.my-gas-station
+fn check_module_name(module: &str) -> Option<()> {
+    if module.is_empty()
+        || module.starts_with('.')
+        || module.ends_with('.')
+        || !module
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c == b'.')
+    {
+        return None;
+    }
+    Some(())
+}
+
 type TxParams1ParserT = (
-    DefaultInterp,
-    (SubDefT, (SubDefT, (SubDefT, (SubDefT, (SubDefT, SubDefT))))),
+    ParamInterp,
+    (
+        ParamInterp,
+        (ParamInterp, (ParamInterp, (ParamInterp, ParamInterp))),
+    ),
 );
 const TX_PARAMS1_PARSER: TxParams1ParserT = (
-    DefaultInterp,
-    (SUB_DEF, (SUB_DEF, (SUB_DEF, (SUB_DEF, (SUB_DEF, SUB_DEF))))),
+    ParamInterp,
+    (
+        ParamInterp,
+        (ParamInterp, (ParamInterp, (ParamInterp, ParamInterp))),
+    ),
 );
 
-pub type RecipientAmountT =
-    impl InterpParser<MakeTransferTxParameters1, Returning = HasherAndPrivKey>;
-
-const RECIPIENT_AMOUNT_PARSER: RecipientAmountT
-  = MoveAction(
-      TX_PARAMS1_PARSER
-    , mkmvfn(|(tx_type, optv1): <TxParams1ParserT as ParserCommon<MakeTransferTxParameters1>>::Returning
-             , destination:&mut Option<HasherAndPrivKey>| {
-        let (recipient, optv2) = optv1?;
-        let (recipient_chain, optv3) = optv2?;
-        let (network, optv4) = optv3?;
-        let (amount, optv5) = optv4?;
-        let (namespace, mod_name) = optv5?;
-        match destination {
-            Some((ref mut hasher, privkey)) => {
-                let mut pkh_str: ArrayString<64> = ArrayString::new();
-                {
-                    with_public_keys_int(privkey, |_: &_, pkh: &PKH| { try_option({
-                        write!(mk_prompt_write(&mut pkh_str), "{}", pkh).ok()
-                    })}).ok()?;
-                }
-                handle_tx_param_1(&pkh_str, hasher, tx_type?, recipient.as_ref()?, recipient_chain.as_ref()?, amount.as_ref()?, network.as_ref()?, namespace.as_ref()?, mod_name.as_ref()?)?;
+pub type RecipientAmountT = impl InterpParser<MakeTransferTxParameters1, Returning = MakeTxContext>;
+
+const RECIPIENT_AMOUNT_PARSER: RecipientAmountT =
+    MoveAction(
+        TX_PARAMS1_PARSER,
+        mkmvfn(
+            |(recipient, optv1): <TxParams1ParserT as ParserCommon<
+                MakeTransferTxParameters1,
+            >>::Returning,
+             destination: &mut Option<MakeTxContext>| {
+                let (recipient_chain, optv2) = optv1?;
+                let (network, optv3) = optv2?;
+                let (amount, optv4) = optv3?;
+                let (namespace, mod_name) = optv4?;
+                let context = destination.as_mut()?;
+                let (recipient, recipient_chain, network, amount, namespace, mod_name) = (
+                    context.param(recipient?),
+                    context.param(recipient_chain?),
+                    context.param(network?),
+                    context.param(amount?),
+                    context.param(namespace?),
+                    context.param(mod_name?),
+                );
+                handle_tx_param_1(
+                    context,
+                    recipient.as_ref()?,
+                    recipient_chain.as_ref()?,
+                    amount.as_ref()?,
+                    network.as_ref()?,
+                    namespace.as_ref()?,
+                    mod_name.as_ref()?,
+                )
+            },
+        ),
+    );
+
+type ContinuationParamsParserT = (ParamInterp, (ParamInterp, (ParamInterp, DefaultInterp)));
+
+pub type ContinuationParserT =
+    impl InterpParser<ContinuationTxParameters, Returning = MakeTxContext>;
+
+const CONTINUATION_PARSER: ContinuationParserT = MoveAction(
+    (ParamInterp, (ParamInterp, (ParamInterp, DefaultInterp))),
+    mkmvfn(
+        |(network, optv1): <ContinuationParamsParserT as ParserCommon<
+            ContinuationTxParameters,
+        >>::Returning,
+         destination: &mut Option<MakeTxContext>| {
+            let (pact_id, optv2) = optv1?;
+            let (step, rollback) = optv2?;
+            let context = destination.as_mut()?;
+            let (network, pact_id, step) = (
+                context.param(network?),
+                context.param(pact_id?),
+                context.param(step?),
+            );
+            handle_cont_params(
+                context,
+                network.as_ref()?,
+                pact_id.as_ref()?,
+                step.as_ref()?,
+                rollback?,
+            )
+        },
+    ),
+);
+
+// The proof is hashed as it is received, as it is usually too large to be kept
+pub struct ContinuationProofParser;
+
+pub enum ContinuationProofState {
+    Length(ArrayVec<u8, 4>),
+    Proof { remaining: u32, attached: bool },
+}
+
+impl ParserCommon<StreamedString> for ContinuationProofParser {
+    type State = ContinuationProofState;
+    type Returning = MakeTxContext;
+    fn init(&self) -> Self::State {
+        ContinuationProofState::Length(ArrayVec::new())
+    }
+}
 
+impl InterpParser<StreamedString> for ContinuationProofParser {
+    #[inline(never)]
+    fn parse<'a>(
+        &self,
+        state: &mut Self::State,
+        chunk: &'a [u8],
+        destination: &mut Option<Self::Returning>,
+    ) -> ParseResult<'a> {
+        let mut cursor = chunk;
+        let context = destination.as_mut().ok_or((Some(OOB::Reject), cursor))?;
+        loop {
+            match state {
+                ContinuationProofState::Length(ref mut length) => {
+                    let (b, rest) = cursor.split_first().ok_or((None, cursor))?;
+                    cursor = rest;
+                    length.push(*b);
+                    if length.is_full() {
+                        let remaining =
+                            u32::from_le_bytes([length[0], length[1], length[2], length[3]]);
+                        let attached = remaining > 0;
+                        write!(context.hasher, "{}", if attached { "\"" } else { "null" })
+                            .or(Err((Some(OOB::Reject), cursor)))?;
+                        *state = ContinuationProofState::Proof {
+                            remaining,
+                            attached,
+                        };
+                    }
+                }
+                ContinuationProofState::Proof {
+                    ref mut remaining, ..
+                } if *remaining > 0 => {
+                    if cursor.is_empty() {
+                        break Err((None, cursor));
+                    }
+                    let (proof, rest) = cursor.split_at(cursor.len().min(*remaining as usize));
+                    // The proof is an unpadded base64url string, so it needs no escaping
+                    if !proof.iter().all(|c| is_base64url(*c)) {
+                        break Err((Some(OOB::Reject), cursor));
+                    }
+                    let proof = from_utf8(proof).or(Err((Some(OOB::Reject), cursor)))?;
+                    context
+                        .hasher
+                        .write_str(proof)
+                        .or(Err((Some(OOB::Reject), cursor)))?;
+                    *remaining -= proof.len() as u32;
+                    cursor = rest;
+                }
+                ContinuationProofState::Proof { attached, .. } => {
+                    let attached = *attached;
+                    let mut finish = || -> Option<()> {
+                        let hasher = &mut context.hasher;
+                        if attached {
+                            write!(hasher, "\"").ok()?;
+                        }
+                        write!(hasher, "}}}}").ok()?;
+                        write!(hasher, ",\"signers\":[{{\"pubKey\":").ok()?;
+                        write!(hasher, "\"{}\"", context.pkh_str).ok()?;
+                        write!(hasher, ",\"clist\":[").ok()?;
+                        write_gas_cap(hasher, &context.sender, &context.gas_payer)?;
+                        write!(hasher, "]}}]").ok()?;
+                        scroller("SPV Proof", |w| {
+                            Ok(write!(w, "{}", if attached { "Attached" } else { "None" })?)
+                        })
+                    };
+                    finish().ok_or((Some(OOB::Reject), cursor))?;
+                    break Ok(cursor);
+                }
             }
-            _ => { panic!("should have been set") }
         }
-        Some(())
-    }),
-    );
+    }
+}
 
-type TxParams2ParserT = (SubDefT, (SubDefT, (SubDefT, (SubDefT, (SubDefT, SubDefT)))));
-const TX_PARAMS2_PARSER: TxParams2ParserT =
-    (SUB_DEF, (SUB_DEF, (SUB_DEF, (SUB_DEF, (SUB_DEF, SUB_DEF)))));
+type TxParams2ParserT = (
+    ParamInterp,
+    (
+        ParamInterp,
+        (ParamInterp, (ParamInterp, (ParamInterp, ParamInterp))),
+    ),
+);
+const TX_PARAMS2_PARSER: TxParams2ParserT = (
+    ParamInterp,
+    (
+        ParamInterp,
+        (ParamInterp, (ParamInterp, (ParamInterp, ParamInterp))),
+    ),
+);
 
-pub type MetaNonceT = impl InterpParser<MakeTransferTxParameters2, Returning = HasherAndPrivKey>;
+pub type MetaNonceT = impl InterpParser<MakeTransferTxParameters2, Returning = MakeTxContext>;
 
 const META_NONCE_PARSER: MetaNonceT =
     MoveAction(
@@ -1007,59 +3852,129 @@ const META_NONCE_PARSER: MetaNonceT =
             |(gas_price, optv1): <TxParams2ParserT as ParserCommon<
                 MakeTransferTxParameters2,
             >>::Returning,
-             destination: &mut Option<HasherAndPrivKey>| {
+             destination: &mut Option<MakeTxContext>| {
                 let (gas_limit, optv2) = optv1?;
                 let (creation_time, optv3) = optv2?;
                 let (chain_id, optv4) = optv3?;
                 let (nonce, ttl) = optv4?;
-                match destination {
-                    Some((ref mut hasher, privkey)) => {
-                        let mut pkh_str: ArrayString<64> = ArrayString::new();
-                        {
-                            with_public_keys_int(privkey, |_: &_, pkh: &PKH| {
-                                try_option({
-                                    write!(mk_prompt_write(&mut pkh_str), "{}", pkh).ok()
-                                })
-                            })
-                            .ok()?;
-                        }
-                        handle_tx_params_2(
-                            &pkh_str,
-                            hasher,
-                            &gas_price?,
-                            &gas_limit?,
-                            &creation_time?,
-                            &chain_id?,
-                            &nonce?,
-                            &ttl?,
-                        )?;
-                    }
-                    _ => {
-                        panic!("destination should have been set")
-                    }
-                }
-                Some(())
+                let context = destination.as_mut()?;
+                let (gas_price, gas_limit, creation_time, chain_id, nonce, ttl) = (
+                    context.param(gas_price?),
+                    context.param(gas_limit?),
+                    context.param(creation_time?),
+                    context.param(chain_id?),
+                    context.param(nonce?),
+                    context.param(ttl?),
+                );
+                handle_tx_params_2(
+                    context,
+                    &gas_price?,
+                    &gas_limit?,
+                    &creation_time?,
+                    &chain_id?,
+                    &nonce?,
+                    &ttl?,
+                )
             },
         ),
     );
 
-pub type MakeTransferTxImplT =
-    impl InterpParser<MakeTransferTxParameters, Returning = ArrayVec<u8, 128_usize>>;
+// The signature and public key; when asked for, the length and hash of the command are returned
+// first, in a reply of their own
+pub const MAKE_TRANSFER_TX_RESPONSE_SIZE: usize = 64 + 32;
+
+pub type MakeTransferTxImplT = impl InterpParser<
+    MakeTransferTxParameters,
+    Returning = ApduResult<MAKE_TRANSFER_TX_RESPONSE_SIZE>,
+>;
 
 pub struct MakeTx;
 pub static MAKE_TRANSFER_TX_IMPL: MakeTransferTxImplT = MakeTx;
 
 pub enum MakeTxSubState {
     Init,
-    Path(<PathParserT as ParserCommon<Bip32Key>>::State),
+    Path(
+        <SubDefT as ParserCommon<Bip32Key>>::State,
+        Option<<SubDefT as ParserCommon<Bip32Key>>::Returning>,
+    ),
+    TxType(<TxTypeParserT as ParserCommon<Byte>>::State),
+    Sender(<SenderParserT as ParserCommon<ByteDArray<PARAM_SENDER_SIZE>>>::State),
+    RecipientKind(<RecipientKindParserT as ParserCommon<Byte>>::State),
+    RecipientGuard(<RecipientGuardParserT as ParserCommon<RecipientGuard>>::State),
+    GasPayer(<GasPayerAccountParserT as ParserCommon<GasPayer>>::State),
+    Continuation(<ContinuationParserT as ParserCommon<ContinuationTxParameters>>::State),
+    ContinuationProof(<ContinuationProofParser as ParserCommon<StreamedString>>::State),
     RecipientAmount(<RecipientAmountT as ParserCommon<MakeTransferTxParameters1>>::State),
     MetaNonce(<MetaNonceT as ParserCommon<MakeTransferTxParameters2>>::State),
-    Done,
+    // The hash of the command, once its length and hash have been returned
+    SignRequest([u8; 32]),
+    Done([u8; 32]),
+}
+
+// The optional parameters follow the txType in the order of the states, when they are flagged
+impl MakeTxSubState {
+    fn after_tx_type(context: &MakeTxContext) -> Self {
+        if context.tx_type & TX_FLAG_NAMED_SENDER != 0 {
+            MakeTxSubState::Sender(<SenderParserT as ParserCommon<
+                ByteDArray<PARAM_SENDER_SIZE>,
+            >>::init(&SENDER_PARSER))
+        } else {
+            Self::after_sender(context)
+        }
+    }
+
+    fn after_sender(context: &MakeTxContext) -> Self {
+        if context.tx_type & TX_FLAG_RECIPIENT_KIND != 0 {
+            MakeTxSubState::RecipientKind(<RecipientKindParserT as ParserCommon<Byte>>::init(
+                &RECIPIENT_KIND_PARSER,
+            ))
+        } else {
+            Self::after_recipient_kind(context)
+        }
+    }
+
+    fn after_recipient_kind(context: &MakeTxContext) -> Self {
+        if context.has_guard() {
+            MakeTxSubState::RecipientGuard(<RecipientGuardParserT as ParserCommon<
+                RecipientGuard,
+            >>::init(&RECIPIENT_GUARD_PARSER))
+        } else {
+            Self::after_recipient_guard(context)
+        }
+    }
+
+    fn after_recipient_guard(context: &MakeTxContext) -> Self {
+        if context.tx_type & TX_FLAG_GAS_PAYER != 0 {
+            MakeTxSubState::GasPayer(<GasPayerAccountParserT as ParserCommon<GasPayer>>::init(
+                &GAS_PAYER_PARSER,
+            ))
+        } else {
+            Self::after_gas_payer(context)
+        }
+    }
+
+    fn after_gas_payer(context: &MakeTxContext) -> Self {
+        if context.tx_type & TX_TYPE_MASK == TX_TYPE_CONTINUATION {
+            MakeTxSubState::Continuation(<ContinuationParserT as ParserCommon<
+                ContinuationTxParameters,
+            >>::init(&CONTINUATION_PARSER))
+        } else {
+            MakeTxSubState::RecipientAmount(<RecipientAmountT as ParserCommon<
+                MakeTransferTxParameters1,
+            >>::init(&RECIPIENT_AMOUNT_PARSER))
+        }
+    }
+
+    fn meta_nonce() -> Self {
+        MakeTxSubState::MetaNonce(
+            <MetaNonceT as ParserCommon<MakeTransferTxParameters2>>::init(&META_NONCE_PARSER),
+        )
+    }
 }
 
 impl ParserCommon<MakeTransferTxParameters> for MakeTx {
-    type State = (Option<HasherAndPrivKey>, MakeTxSubState);
-    type Returning = ArrayVec<u8, 128_usize>;
+    type State = (Option<MakeTxContext>, MakeTxSubState);
+    type Returning = ApduResult<MAKE_TRANSFER_TX_RESPONSE_SIZE>;
     fn init(&self) -> Self::State {
         (None, MakeTxSubState::Init)
     }
@@ -1069,9 +3984,32 @@ impl InterpParser<MakeTransferTxParameters> for MakeTx {
     #[inline(never)]
     fn parse<'a>(
         &self,
-        (ref mut hasher_and_privkey, ref mut state): &mut Self::State,
+        (ref mut context, ref mut state): &mut Self::State,
         chunk: &'a [u8],
         destination: &mut Option<Self::Returning>,
+    ) -> ParseResult<'a> {
+        let rv = MakeTx::parse_tx(context, state, chunk, destination);
+        // A parameter which is too long is rejected with its own status word
+        match (
+            rv,
+            context.as_mut().and_then(|context| context.error.take()),
+        ) {
+            (Err((Some(OOB::Reject), cursor)), Some(e)) => {
+                *destination = Some(Err(e));
+                Ok(cursor)
+            }
+            (rv, _) => rv,
+        }
+    }
+}
+
+impl MakeTx {
+    #[inline(never)]
+    fn parse_tx<'a>(
+        context: &mut Option<MakeTxContext>,
+        state: &mut MakeTxSubState,
+        chunk: &'a [u8],
+        destination: &mut Option<ApduResult<MAKE_TRANSFER_TX_RESPONSE_SIZE>>,
     ) -> ParseResult<'a> {
         let mut cursor = chunk;
         loop {
@@ -1081,84 +4019,287 @@ impl InterpParser<MakeTransferTxParameters> for MakeTx {
                         "State sizes \nMakeTx: {}\n",
                         core::mem::size_of::<MakeTxSubState>()
                     );
-                    init_with_default(destination);
                     set_from_thunk(state, || {
-                        MakeTxSubState::Path(<PathParserT as ParserCommon<Bip32Key>>::init(
-                            &PATH_PARSER,
+                        MakeTxSubState::Path(
+                            <SubDefT as ParserCommon<Bip32Key>>::init(&SUB_DEF),
+                            None,
+                        )
+                    })
+                }
+                MakeTxSubState::Path(ref mut sub, ref mut path) => {
+                    cursor =
+                        <SubDefT as InterpParser<Bip32Key>>::parse(&SUB_DEF, sub, cursor, path)?;
+                    let path = path.as_ref().ok_or((Some(OOB::Reject), cursor))?;
+                    match check_path(path.clone()) {
+                        Ok(path) => {
+                            *context = Some(
+                                MakeTxContext::for_path(&path)
+                                    .ok_or((Some(OOB::Reject), cursor))?,
+                            )
+                        }
+                        Err(e) => {
+                            *destination = Some(Err(e));
+                            break Ok(cursor);
+                        }
+                    }
+                    set_from_thunk(state, || {
+                        MakeTxSubState::TxType(<TxTypeParserT as ParserCommon<Byte>>::init(
+                            &TX_TYPE_PARSER,
                         ))
                     })
                 }
-                MakeTxSubState::Path(ref mut sub) => {
-                    cursor = <PathParserT as InterpParser<Bip32Key>>::parse(
-                        &PATH_PARSER,
+                MakeTxSubState::TxType(ref mut sub) => {
+                    cursor = <TxTypeParserT as InterpParser<Byte>>::parse(
+                        &TX_TYPE_PARSER,
+                        sub,
+                        cursor,
+                        context,
+                    )?;
+                    let context = context.as_ref().ok_or((Some(OOB::Reject), cursor))?;
+                    set_from_thunk(state, || MakeTxSubState::after_tx_type(context))
+                }
+                MakeTxSubState::Sender(ref mut sub) => {
+                    cursor = <SenderParserT as InterpParser<ByteDArray<PARAM_SENDER_SIZE>>>::parse(
+                        &SENDER_PARSER,
+                        sub,
+                        cursor,
+                        context,
+                    )?;
+                    let context = context.as_ref().ok_or((Some(OOB::Reject), cursor))?;
+                    set_from_thunk(state, || MakeTxSubState::after_sender(context))
+                }
+                MakeTxSubState::RecipientKind(ref mut sub) => {
+                    cursor = <RecipientKindParserT as InterpParser<Byte>>::parse(
+                        &RECIPIENT_KIND_PARSER,
+                        sub,
+                        cursor,
+                        context,
+                    )?;
+                    let context = context.as_ref().ok_or((Some(OOB::Reject), cursor))?;
+                    set_from_thunk(state, || MakeTxSubState::after_recipient_kind(context))
+                }
+                MakeTxSubState::RecipientGuard(ref mut sub) => {
+                    cursor = <RecipientGuardParserT as InterpParser<RecipientGuard>>::parse(
+                        &RECIPIENT_GUARD_PARSER,
+                        sub,
+                        cursor,
+                        context,
+                    )?;
+                    let context = context.as_ref().ok_or((Some(OOB::Reject), cursor))?;
+                    set_from_thunk(state, || MakeTxSubState::after_recipient_guard(context))
+                }
+                MakeTxSubState::GasPayer(ref mut sub) => {
+                    cursor = <GasPayerAccountParserT as InterpParser<GasPayer>>::parse(
+                        &GAS_PAYER_PARSER,
                         sub,
                         cursor,
-                        hasher_and_privkey,
+                        context,
                     )?;
+                    let context = context.as_ref().ok_or((Some(OOB::Reject), cursor))?;
+                    set_from_thunk(state, || MakeTxSubState::after_gas_payer(context))
+                }
+                MakeTxSubState::Continuation(ref mut sub) => {
+                    cursor =
+                        <ContinuationParserT as InterpParser<ContinuationTxParameters>>::parse(
+                            &CONTINUATION_PARSER,
+                            sub,
+                            cursor,
+                            context,
+                        )?;
                     set_from_thunk(state, || {
-                        MakeTxSubState::RecipientAmount(<RecipientAmountT as ParserCommon<
-                            MakeTransferTxParameters1,
-                        >>::init(
-                            &RECIPIENT_AMOUNT_PARSER
-                        ))
+                        MakeTxSubState::ContinuationProof(
+                            <ContinuationProofParser as ParserCommon<StreamedString>>::init(
+                                &ContinuationProofParser,
+                            ),
+                        )
                     })
                 }
+                MakeTxSubState::ContinuationProof(ref mut sub) => {
+                    cursor = <ContinuationProofParser as InterpParser<StreamedString>>::parse(
+                        &ContinuationProofParser,
+                        sub,
+                        cursor,
+                        context,
+                    )?;
+                    set_from_thunk(state, MakeTxSubState::meta_nonce)
+                }
                 MakeTxSubState::RecipientAmount(ref mut sub) => {
                     cursor = <RecipientAmountT as InterpParser<MakeTransferTxParameters1>>::parse(
                         &RECIPIENT_AMOUNT_PARSER,
                         sub,
                         cursor,
-                        hasher_and_privkey,
+                        context,
+                    )?;
+                    set_from_thunk(state, MakeTxSubState::meta_nonce)
+                }
+                MakeTxSubState::MetaNonce(ref mut sub) => {
+                    cursor = <MetaNonceT as InterpParser<MakeTransferTxParameters2>>::parse(
+                        &META_NONCE_PARSER,
+                        sub,
+                        cursor,
+                        context,
                     )?;
+                    let context = context.as_mut().ok_or((Some(OOB::Reject), cursor))?;
+                    let hash: Base64Hash<32> = context.hasher.hasher.finalize();
+                    if context.tx_type & TX_FLAG_RETURN_COMMAND_HASH != 0 {
+                        // Reply with the length and hash of the command before asking the user to
+                        // sign it, so that the host can check the command it built first
+                        let mut rv = ArrayVec::new();
+                        let mut add_command_hash = || -> Option<()> {
+                            rv.try_extend_from_slice(&context.hasher.len.to_le_bytes())
+                                .ok()?;
+                            rv.try_extend_from_slice(&hash.0).ok()
+                        };
+                        add_command_hash().ok_or((Some(OOB::Reject), cursor))?;
+                        *destination = Some(Ok(rv));
+                        set_from_thunk(state, || MakeTxSubState::SignRequest(hash.0));
+                        break Err((None, cursor));
+                    }
+                    set_from_thunk(state, || MakeTxSubState::Done(hash.0));
+                }
+                MakeTxSubState::SignRequest(hash) => {
+                    let (request, rest) = cursor.split_first().ok_or((None, cursor))?;
+                    if *request != TX_REQUEST_SIGNATURE {
+                        break Err((Some(OOB::Reject), cursor));
+                    }
+                    cursor = rest;
+                    let hash = *hash;
+                    set_from_thunk(state, || MakeTxSubState::Done(hash));
+                }
+                MakeTxSubState::Done(hash) => {
+                    let context = context.as_mut().ok_or((Some(OOB::Reject), cursor))?;
+                    #[allow(clippy::needless_borrow)] // Needed for nanos
+                    if final_accept_prompt(&[&"Sign Transaction?"]).is_none() {
+                        *destination = Some(Err(KadenaError::UserRejected));
+                        break Ok(cursor);
+                    }
+                    let mut rv = ArrayVec::new();
+
+                    let mut add_sig = || -> Option<()> {
+                        let sig = eddsa_sign_int(&context.privkey, &hash[..]).ok()?;
+                        rv.try_extend_from_slice(&sig.0[..]).ok()?;
+                        Some(())
+                    };
+                    add_sig().ok_or((Some(OOB::Reject), cursor))?;
+
+                    with_public_keys_int(&context.privkey, |key: &_, _: &PKH| {
+                        try_option(|| -> Option<()> {
+                            let key_x = ed25519_public_key_bytes(key);
+                            rv.try_extend_from_slice(key_x).ok()
+                        }())
+                    })
+                    .or(Err((Some(OOB::Reject), cursor)))?;
+                    *destination = Some(Ok(rv));
+                    break Ok(cursor);
+                }
+            }
+        }
+    }
+}
+
+pub type SignForPathImplT = impl InterpParser<SignForPathParameters, Returning = ApduResult<128>>;
+
+// Sign with the path given before the transaction, so that the signers can be filtered by its key
+pub struct SignForPath;
+pub static SIGN_FOR_PATH_IMPL: SignForPathImplT = SignForPath;
+
+pub enum SignForPathSubState {
+    Init,
+    Path(<SubDefT as ParserCommon<Bip32Key>>::State),
+    Review(<SignTxReviewImplT as ParserCommon<KadenaCmdJson>>::State),
+    Done,
+}
+
+impl ParserCommon<SignForPathParameters> for SignForPath {
+    type State = (
+        Option<ArrayVec<u32, 10>>,
+        // The key for the path, by which the signers are filtered
+        Option<[u8; 32]>,
+        Option<<SignTxReviewImplT as ParserCommon<KadenaCmdJson>>::Returning>,
+        SignForPathSubState,
+    );
+    type Returning = ApduResult<128>;
+    fn init(&self) -> Self::State {
+        (None, None, None, SignForPathSubState::Init)
+    }
+}
+
+impl InterpParser<SignForPathParameters> for SignForPath {
+    #[inline(never)]
+    fn parse<'a>(
+        &self,
+        (ref mut path, ref mut key, ref mut review, ref mut state): &mut Self::State,
+        chunk: &'a [u8],
+        destination: &mut Option<Self::Returning>,
+    ) -> ParseResult<'a> {
+        let mut cursor = chunk;
+        loop {
+            match state {
+                SignForPathSubState::Init => set_from_thunk(state, || {
+                    SignForPathSubState::Path(<SubDefT as ParserCommon<Bip32Key>>::init(&SUB_DEF))
+                }),
+                SignForPathSubState::Path(ref mut sub) => {
+                    cursor =
+                        <SubDefT as InterpParser<Bip32Key>>::parse(&SUB_DEF, sub, cursor, path)?;
+                    let path = path.as_ref().ok_or((Some(OOB::Reject), cursor))?;
+                    if !path.starts_with(&BIP32_PREFIX[0..2]) {
+                        *destination = Some(Err(KadenaError::InvalidPath));
+                        break Ok(cursor);
+                    }
+                    with_public_keys(path, false, |pub_key: &_, _: &PKH| {
+                        try_option(|| -> Option<()> {
+                            *key = Some(ed25519_public_key_bytes(pub_key).try_into().ok()?);
+                            Some(())
+                        }())
+                    })
+                    .or(Err((Some(OOB::Reject), cursor)))?;
                     set_from_thunk(state, || {
-                        MakeTxSubState::MetaNonce(<MetaNonceT as ParserCommon<
-                            MakeTransferTxParameters2,
+                        SignForPathSubState::Review(<SignTxReviewImplT as ParserCommon<
+                            KadenaCmdJson,
                         >>::init(
-                            &META_NONCE_PARSER
+                            &SIGN_TX_REVIEW_IMPL
                         ))
                     })
                 }
-                MakeTxSubState::MetaNonce(ref mut sub) => {
-                    cursor = <MetaNonceT as InterpParser<MakeTransferTxParameters2>>::parse(
-                        &META_NONCE_PARSER,
+                SignForPathSubState::Review(ref mut sub) => {
+                    // The review for the key is rebuilt for each chunk, as it only holds the key
+                    cursor = <SignTxReviewImplT as InterpParser<KadenaCmdJson>>::parse(
+                        &sign_tx_review_impl(*key),
                         sub,
                         cursor,
-                        hasher_and_privkey,
+                        review,
                     )?;
-                    set_from_thunk(state, || MakeTxSubState::Done);
-                }
-                MakeTxSubState::Done => {
-                    match hasher_and_privkey {
-                        Some((ref mut hasher, privkey)) => {
-                            #[allow(clippy::needless_borrow)] // Needed for nanos
-                            final_accept_prompt(&[&"Sign Transaction?"])
-                                .ok_or((Some(OOB::Reject), cursor))?;
-                            *destination = Some(ArrayVec::new());
-
-                            let mut add_sig = || -> Option<()> {
-                                let hash: Base64Hash<32> = hasher.finalize();
-                                let sig = eddsa_sign_int(privkey, &hash.0).ok()?;
-                                destination
-                                    .as_mut()?
-                                    .try_extend_from_slice(&sig.0[..])
-                                    .ok()?;
+                    set_from_thunk(state, || SignForPathSubState::Done);
+                }
+                SignForPathSubState::Done => {
+                    let mut sign = || -> Option<()> {
+                        let path = path.as_ref()?;
+                        let (hash, signers, totals) = match review.as_ref()? {
+                            Ok(review) => review,
+                            Err(e) => return reject(destination, *e),
+                        };
+                        with_public_keys(path, false, |_, pkh: &PKH| {
+                            try_option(|| -> Option<()> {
+                                scroller("Sign for Address", |w| Ok(write!(w, "{pkh}")?))?;
                                 Some(())
-                            };
-                            add_sig().ok_or((Some(OOB::Reject), cursor))?;
-
-                            with_public_keys_int(privkey, |key: &_, _: &PKH| {
-                                try_option(|| -> Option<()> {
-                                    let key_x = ed25519_public_key_bytes(key);
-                                    destination.as_mut()?.try_extend_from_slice(key_x).ok()
-                                }())
-                            })
-                            .or(Err((Some(OOB::Reject), cursor)))?;
-                            break Ok(cursor);
-                        }
-                        _ => {
-                            panic!("should have been set")
+                            }())
+                        })
+                        .ok()?;
+                        check_signer(signers, path, None)?;
+                        show_transfer_totals(totals)?;
+                        #[allow(clippy::needless_borrow)] // Needed for nanos
+                        if final_accept_prompt(&[&"Sign Transaction?"]).is_none() {
+                            return reject(destination, KadenaError::UserRejected);
                         }
-                    }
+
+                        let sig = eddsa_sign(path, false, &hash.0[..]).ok()?;
+                        let mut rv = ArrayVec::new();
+                        rv.try_extend_from_slice(&sig.0[..]).ok()?;
+                        *destination = Some(Ok(rv));
+                        Some(())
+                    };
+                    sign().ok_or((Some(OOB::Reject), cursor))?;
+                    break Ok(cursor);
                 }
             }
         }
@@ -1173,6 +4314,8 @@ pub enum ParsersState {
     SettingsState(u8),
     GetAddressState(<GetAddressImplT as ParserCommon<Bip32Key>>::State),
     SignState(<SignImplT as ParserCommon<SignParameters>>::State),
+    SignMultiState(<SignMultiImplT as ParserCommon<SignMultiParameters>>::State),
+    SignForPathState(<SignForPathImplT as ParserCommon<SignForPathParameters>>::State),
     SignHashState(<SignHashImplT as ParserCommon<SignHashParameters>>::State),
     MakeTransferTxState(<MakeTransferTxImplT as ParserCommon<MakeTransferTxParameters>>::State),
 }
@@ -1184,8 +4327,11 @@ pub fn reset_parsers_state(state: &mut ParsersState) {
 meta_definition! {}
 kadena_capability_definition! {}
 signer_definition! {}
+keyset_definition! {}
 payload_definition! {}
 command_definition! {}
+cont_payload_definition! {}
+continuation_definition! {}
 kadena_cmd_definition! {}
 
 #[inline(never)]
@@ -1230,6 +4376,48 @@ pub fn get_sign_state(
     }
 }
 
+#[inline(never)]
+pub fn get_sign_multi_state(
+    s: &mut ParsersState,
+) -> &mut <SignMultiImplT as ParserCommon<SignMultiParameters>>::State {
+    match s {
+        ParsersState::SignMultiState(_) => {}
+        _ => {
+            info!("Non-same state found; initializing state.");
+            *s = ParsersState::SignMultiState(<SignMultiImplT as ParserCommon<
+                SignMultiParameters,
+            >>::init(&SIGN_MULTI_IMPL));
+        }
+    }
+    match s {
+        ParsersState::SignMultiState(ref mut a) => a,
+        _ => {
+            unreachable!("Should be impossible because assignment right above")
+        }
+    }
+}
+
+#[inline(never)]
+pub fn get_sign_for_path_state(
+    s: &mut ParsersState,
+) -> &mut <SignForPathImplT as ParserCommon<SignForPathParameters>>::State {
+    match s {
+        ParsersState::SignForPathState(_) => {}
+        _ => {
+            info!("Non-same state found; initializing state.");
+            *s = ParsersState::SignForPathState(<SignForPathImplT as ParserCommon<
+                SignForPathParameters,
+            >>::init(&SIGN_FOR_PATH_IMPL));
+        }
+    }
+    match s {
+        ParsersState::SignForPathState(ref mut a) => a,
+        _ => {
+            unreachable!("Should be impossible because assignment right above")
+        }
+    }
+}
+
 #[inline(never)]
 pub fn get_sign_hash_state(
     s: &mut ParsersState,
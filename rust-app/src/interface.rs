@@ -1,5 +1,5 @@
 use core::convert::TryFrom;
-use ledger_device_sdk::io::{ApduHeader, StatusWords};
+use ledger_device_sdk::io::{ApduHeader, Reply, StatusWords};
 use ledger_parser_combinators::core_parsers::*;
 use ledger_parser_combinators::define_json_struct;
 use ledger_parser_combinators::endianness::*;
@@ -9,56 +9,94 @@ use num_enum::TryFromPrimitive;
 // Payload for a public key request
 pub type Bip32Key = DArray<Byte, U32<{ Endianness::Little }>, 10>;
 
+// The Alts allow a sender, ttl or creation time which is too long to be shown to be dropped, and a
+// chain or gas which is too long to be told apart from one which is missing
 define_json_struct! { Meta 16 {
-    chainId: JsonString,
-    sender: JsonString,
-    gasLimit: JsonNumber,
-    gasPrice: JsonNumber,
-    ttl: JsonNumber,
-    creationTime: JsonNumber
+    chainId: Alt<JsonString, JsonString>,
+    sender: Alt<JsonString, JsonString>,
+    gasLimit: Alt<JsonNumber, JsonNumber>,
+    gasPrice: Alt<JsonNumber, JsonNumber>,
+    ttl: Alt<JsonNumber, JsonNumber>,
+    creationTime: Alt<JsonNumber, JsonNumber>
 }}
 
+// The Alt allows a name which is too long to be accumulated to be dropped
 define_json_struct! { KadenaCapability 4 {
     args: JsonArray<JsonAny>,
-    name: JsonString
+    name: Alt<JsonString, JsonString>
 }}
 
+// The Alt allows a key which is too long to be a public key to be counted and dropped
+define_json_struct! { Keyset 4 {
+    keys: JsonArray<Alt<JsonString, JsonString>>,
+    pred: JsonString
+}}
+
+// The Alt allows a pubKey which is too long to be shown to be dropped
 define_json_struct! { Signer 16 {
     scheme: JsonString,
-    pubKey: JsonString,
+    pubKey: Alt<JsonString, JsonString>,
     addr: JsonString,
     clist: Alt<JsonNull,JsonArray<KadenaCapabilitySchema>>
 }}
 
+// The Alt allows the code to be dropped when it is too large to be accumulated for display
 define_json_struct! { Command 5 {
     data: JsonAny,
-    code: JsonString
+    code: Alt<JsonString, JsonString>
 }}
 
 define_json_struct! { Payload 5 {
     exec: CommandSchema
 }}
 
+// The Alts allow a field which is too long to be told apart from one which is missing
+define_json_struct! { Continuation 8 {
+    pactId: Alt<JsonString, JsonString>,
+    step: Alt<JsonNumber, JsonNumber>,
+    rollback: Alt<JsonAny, JsonAny>,
+    data: JsonAny,
+    proof: Alt<JsonString, JsonNull>
+}}
+
+define_json_struct! { ContPayload 5 {
+    cont: ContinuationSchema
+}}
+
+// The Alt allows a nonce which is too long to be shown to be dropped
 define_json_struct! { KadenaCmd 16 {
-  nonce: JsonString,
+  nonce: Alt<JsonString, JsonString>,
   meta: Alt<MetaSchema, JsonAny>,
   signers: JsonArray<SignerSchema>,
-  payload: PayloadSchema,
+  payload: Alt<PayloadSchema, ContPayloadSchema>,
   networkId: Alt<JsonString,JsonNull>
 }}
 
+pub type KadenaCmdJson = LengthFallback<U32<{ Endianness::Little }>, Json<KadenaCmdSchema>>;
+
 // Payload for a signature request, content-agnostic.
-pub type SignParameters = (
-    LengthFallback<U32<{ Endianness::Little }>, Json<KadenaCmdSchema>>,
-    Bip32Key,
-);
+pub type SignParameters = (KadenaCmdJson, Bip32Key);
+
+// A public key and signature (32 + 64 bytes) is returned for each path, and the response has to
+// fit in a single APDU.
+pub const MAX_SIGN_PATHS: usize = 2;
+
+pub type SignMultiParameters = (KadenaCmdJson, DArray<Byte, Bip32Key, MAX_SIGN_PATHS>);
+
+// The path comes first, so that the signers can be shown according to its key
+pub type SignForPathParameters = (Bip32Key, KadenaCmdJson);
 
 pub type SignHashParameters = (Array<Byte, 32>, Bip32Key);
 
 pub type ByteDArray<const N: usize> = DArray<Byte, Byte, N>;
 
 pub const PARAM_AMOUNT_SIZE: usize = 32;
+// A named recipient may be as long as a named sender, except on the Nano S where it may be as long
+// as the key of a k: account
+#[cfg(target_os = "nanos")]
 pub const PARAM_RECIPIENT_SIZE: usize = 64;
+#[cfg(not(target_os = "nanos"))]
+pub const PARAM_RECIPIENT_SIZE: usize = PARAM_SENDER_SIZE;
 pub const PARAM_RECIPIENT_CHAIN_SIZE: usize = 2;
 pub const PARAM_NETWORK_SIZE: usize = 20;
 pub const PARAM_NAMESPACE_SIZE: usize = 16;
@@ -71,26 +109,105 @@ pub const PARAM_CHAIN_SIZE: usize = 2;
 pub const PARAM_NOONCE_SIZE: usize = 32;
 pub const PARAM_TTL_SIZE: usize = 20;
 
+// The optional parameters which follow the txType, according to its flags, are not part of the
+// type
 pub type MakeTransferTxParameters = (
     Bip32Key,
+    Byte, // txType
     MakeTransferTxParameters1,
     MakeTransferTxParameters2,
 );
 
+// The txType holds the type of transaction in its low bits, and flags for the optional
+// parameters in its high bits
+pub const TX_TYPE_MASK: u8 = 0x0f;
+// The continuation of a cross-chain transfer, on the target chain; its parameters are given by
+// ContinuationTxParameters and the proof, instead of MakeTransferTxParameters1
+pub const TX_TYPE_CONTINUATION: u8 = 3;
+// The name of the sender account follows the txType, instead of the k: account of the key
+pub const TX_FLAG_NAMED_SENDER: u8 = 0x80;
+// The kind of the recipient account follows the sender, and the recipient is given as is rather
+// than as the key of a k: account
+pub const TX_FLAG_RECIPIENT_KIND: u8 = 0x40;
+// The gas payer follows the guard of the recipient, and pays for gas instead of the sender
+pub const TX_FLAG_GAS_PAYER: u8 = 0x20;
+// The length and hash of the command are returned in reply to the chunk which completes the
+// parameters, before the user is asked to sign; a further chunk holding TX_REQUEST_SIGNATURE asks
+// for the signature
+pub const TX_FLAG_RETURN_COMMAND_HASH: u8 = 0x10;
+pub const TX_REQUEST_SIGNATURE: u8 = 0x01;
+pub const TX_FLAGS_SUPPORTED: u8 =
+    TX_FLAG_NAMED_SENDER | TX_FLAG_RECIPIENT_KIND | TX_FLAG_GAS_PAYER | TX_FLAG_RETURN_COMMAND_HASH;
+
+// Account names are 3 to 256 characters, but the length of a parameter is a byte, so a named sender
+// or gas payer is up to 255 characters; the Nano S only keeps up to the length of a k: account
+#[cfg(target_os = "nanos")]
+pub const PARAM_SENDER_SIZE: usize = 66;
+#[cfg(not(target_os = "nanos"))]
+pub const PARAM_SENDER_SIZE: usize = 255;
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+pub enum RecipientKind {
+    KAccount = 0,
+    WAccount = 1,
+    Named = 2,
+}
+
+// The guard of a w: or named recipient, which follows its kind: a predicate and the keys
+pub type RecipientGuard = (
+    ByteDArray<PARAM_GUARD_PRED_SIZE>,
+    DArray<Byte, Array<Byte, 32>, PARAM_GUARD_KEYS>,
+);
+
+pub const PARAM_GUARD_PRED_SIZE: usize = 8;
+#[cfg(target_os = "nanos")]
+pub const PARAM_GUARD_KEYS: usize = 3;
+#[cfg(not(target_os = "nanos"))]
+pub const PARAM_GUARD_KEYS: usize = 10;
+
+// The gas payer account, the module of its GAS_PAYER capability, and the limit and price arguments
+// of the capability
+pub type GasPayer = (
+    ByteDArray<PARAM_SENDER_SIZE>,
+    (
+        ByteDArray<PARAM_GAS_PAYER_MODULE_SIZE>,
+        (
+            ByteDArray<PARAM_GAS_LIMIT_SIZE>,
+            ByteDArray<PARAM_GAS_PRICE_SIZE>,
+        ),
+    ),
+);
+
+pub const PARAM_GAS_PAYER_MODULE_SIZE: usize = 64;
+
+// The network, pact id, step and rollback flag of a continuation
+pub type ContinuationTxParameters = (
+    ByteDArray<PARAM_NETWORK_SIZE>,
+    (
+        ByteDArray<PARAM_PACT_ID_SIZE>,
+        (ByteDArray<PARAM_STEP_SIZE>, Byte),
+    ),
+);
+
+pub const PARAM_PACT_ID_SIZE: usize = 43;
+pub const PARAM_STEP_SIZE: usize = 4;
+
+// A string of any length, given as a u32 (little endian) length and the bytes, which is hashed as
+// it is received rather than kept; the SPV proof of a continuation
+pub struct StreamedString;
+
 pub type MakeTransferTxParameters1 = (
-    Byte, // txType
+    ByteDArray<PARAM_RECIPIENT_SIZE>,
     (
-        ByteDArray<PARAM_RECIPIENT_SIZE>,
+        ByteDArray<PARAM_RECIPIENT_CHAIN_SIZE>,
         (
-            ByteDArray<PARAM_RECIPIENT_CHAIN_SIZE>,
+            ByteDArray<PARAM_NETWORK_SIZE>,
             (
-                ByteDArray<PARAM_NETWORK_SIZE>,
+                ByteDArray<PARAM_AMOUNT_SIZE>,
                 (
-                    ByteDArray<PARAM_AMOUNT_SIZE>,
-                    (
-                        ByteDArray<PARAM_NAMESPACE_SIZE>,
-                        ByteDArray<PARAM_MOD_NAME_SIZE>,
-                    ),
+                    ByteDArray<PARAM_NAMESPACE_SIZE>,
+                    ByteDArray<PARAM_MOD_NAME_SIZE>,
                 ),
             ),
         ),
@@ -112,29 +229,103 @@ pub type MakeTransferTxParameters2 = (
 );
 
 #[repr(u8)]
-#[derive(Debug, TryFromPrimitive)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
 pub enum Ins {
     GetVersion = 0,
     VerifyAddress = 1,
     GetPubkey = 2,
     Sign = 3,
     SignHash = 4,
+    SignMulti = 5,
+    SignForPath = 6,
+    GetAppConfiguration = 7,
     MakeTransferTx = 0x10,
     GetVersionStr = 0xfe,
     Exit = 0xff,
 }
 
-impl TryFrom<ApduHeader> for Ins {
+// The flags and device model returned by GET_APP_CONFIGURATION
+pub const CONFIG_BLIND_SIGNING_ENABLED: u8 = 0x01;
+pub const CONFIG_EXPERT_MODE_ENABLED: u8 = 0x02;
+// The Hash Display setting is in bits 2 and 3
+pub const CONFIG_HASH_DISPLAY_SHIFT: u8 = 2;
+
+#[cfg(target_os = "nanos")]
+pub const DEVICE_MODEL: u8 = 0;
+#[cfg(target_os = "nanox")]
+pub const DEVICE_MODEL: u8 = 1;
+#[cfg(target_os = "nanosplus")]
+pub const DEVICE_MODEL: u8 = 2;
+#[cfg(not(any(target_os = "nanos", target_os = "nanox", target_os = "nanosplus")))]
+pub const DEVICE_MODEL: u8 = 0xff;
+
+// The P1 flags of the chunks of a sequenced message; either FIRST or CONTINUE is set, and LAST
+// may be added to either. A P1 of 0 leaves the chunks unsequenced.
+pub const P1_FIRST: u8 = 0x01;
+pub const P1_CONTINUE: u8 = 0x02;
+pub const P1_LAST: u8 = 0x04;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chunk {
+    // Joined to the unsequenced message in progress for the same INS, if any; an unsequenced
+    // message in progress for another INS is dropped with an error
+    Unsequenced,
+    // Starts a new message, dropping the one in progress
+    First { last: bool },
+    // The index of the chunk in its message (modulo 256) is given by P2
+    Continue { index: u8, last: bool },
+}
+
+impl Chunk {
+    pub fn is_last(&self) -> bool {
+        match self {
+            Chunk::Unsequenced => false,
+            Chunk::First { last } | Chunk::Continue { last, .. } => *last,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ApduCommand {
+    pub ins: Ins,
+    pub chunk: Chunk,
+}
+
+impl TryFrom<ApduHeader> for ApduCommand {
     type Error = StatusWords;
-    fn try_from(m: ApduHeader) -> Result<Ins, Self::Error> {
-        match m {
-            ApduHeader {
-                cla: 0,
-                ins,
-                p1: 0,
-                p2: 0,
-            } => Self::try_from(ins).map_err(|_| StatusWords::BadIns),
-            _ => Err(StatusWords::BadIns),
+    fn try_from(m: ApduHeader) -> Result<ApduCommand, Self::Error> {
+        if m.cla != 0 {
+            return Err(StatusWords::BadIns);
         }
+        let ins = Ins::try_from(m.ins).map_err(|_| StatusWords::BadIns)?;
+        let last = m.p1 & P1_LAST != 0;
+        let chunk = match (m.p1 & !P1_LAST, m.p2) {
+            (0, 0) if !last => Chunk::Unsequenced,
+            (P1_FIRST, 0) => Chunk::First { last },
+            (P1_CONTINUE, index) => Chunk::Continue { index, last },
+            _ => return Err(StatusWords::BadP1P2),
+        };
+        Ok(ApduCommand { ins, chunk })
+    }
+}
+
+// The status words of the errors specific to this app; see docs/apdu.md
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KadenaError {
+    BlindSigningDisabled = 0x6808,
+    UserRejected = 0x6985,
+    InvalidInput = 0x6D00,
+    InvalidPath = 0xB001,
+    InvalidJson = 0xB002,
+    FieldTooLong = 0xB003,
+    TrailingData = 0xB004,
+    WrongParserState = 0xB005,
+    OutOfOrderChunk = 0xB006,
+}
+
+impl From<KadenaError> for Reply {
+    fn from(e: KadenaError) -> Reply {
+        Reply(e as u16)
     }
 }
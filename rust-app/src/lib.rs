@@ -31,6 +31,10 @@ pub mod interface;
 #[cfg(target_family = "bolos")]
 pub mod utils;
 
+pub mod decimal;
+
+pub mod pact;
+
 #[cfg(target_family = "bolos")]
 pub mod implementation;
 
@@ -3,6 +3,7 @@ use crate::interface::*;
 use crate::menu::*;
 use crate::settings::*;
 
+use core::convert::TryFrom;
 use core::fmt::Write;
 use ledger_device_sdk::io;
 use ledger_device_sdk::uxapp::{UxEvent, BOLOS_UX_OK};
@@ -16,6 +17,7 @@ use ledger_prompts_ui::{handle_menu_button_event, show_menu};
 pub fn app_main() {
     let mut comm = io::Comm::new();
     let mut states = ParsersState::NoState;
+    let mut sequence = None;
     let mut idle_menu = IdleMenuWithSettings {
         idle_menu: IdleMenu::AppMain,
         settings: Settings,
@@ -40,10 +42,16 @@ pub fn app_main() {
         info!("Fetching next event.");
         // Wait for either a specific button push to exit the app
         // or an APDU command
-        match comm.next_event::<Ins>() {
-            io::Event::Command(ins) => {
+        match comm.next_event::<ApduCommand>() {
+            io::Event::Command(command) => {
                 trace!("Command received");
-                match handle_apdu(&mut comm, ins, &mut states, &idle_menu.settings) {
+                match handle_command(
+                    &mut comm,
+                    command,
+                    &mut states,
+                    &mut sequence,
+                    &idle_menu.settings,
+                ) {
                     Ok(()) => {
                         trace!("APDU accepted; sending response");
                         comm.reply_ok();
@@ -70,7 +78,8 @@ pub fn app_main() {
                     _ => {
                         if let Some(DoCancel) = handle_menu_button_event(&mut busy_menu, btn) {
                             info!("Resetting at user direction via busy menu");
-                            reset_parsers_state(&mut states)
+                            reset_parsers_state(&mut states);
+                            sequence = None;
                         }
                     }
                 };
@@ -89,14 +98,14 @@ pub fn app_main() {
     }
 }
 
-use arrayvec::ArrayVec;
 use ledger_device_sdk::io::Reply;
 
 use ledger_parser_combinators::interp_parser::{InterpParser, ParserCommon};
-fn run_parser_apdu<P: InterpParser<A, Returning = ArrayVec<u8, 128>>, A>(
+fn run_parser_apdu<P: InterpParser<A, Returning = ApduResult<N>>, A, const N: usize>(
     states: &mut ParsersState,
     get_state: fn(&mut ParsersState) -> &mut <P as ParserCommon<A>>::State,
     parser: &P,
+    invalid_input: KadenaError,
     comm: &mut io::Comm,
 ) -> Result<(), Reply> {
     let cursor = comm.get_data()?;
@@ -107,43 +116,130 @@ fn run_parser_apdu<P: InterpParser<A, Returning = ArrayVec<u8, 128>>, A>(
         <P as InterpParser<A>>::parse(parser, get_state(states), cursor, &mut parse_destination);
     trace!("Parser result: {:?}\n", parse_rv);
     match parse_rv {
-        // Explicit rejection; reset the parser. Possibly send error message to host?
+        // Explicit rejection; reset the parser.
         Err((Some(OOB::Reject), _)) => {
             reset_parsers_state(states);
-            Err(io::StatusWords::Unknown.into())
+            Err(invalid_input.into())
         }
         // Deliberately no catch-all on the Err((Some case; we'll get error messages if we
         // add to OOB's out-of-band actions and forget to implement them.
         //
-        // Finished the chunk with no further actions pending, but not done.
+        // Finished the chunk with no further actions pending, but not done; the parser may have
+        // replied to it all the same.
         Err((None, [])) => {
             trace!("Parser needs more; continuing");
+            if let Some(Ok(rv)) = parse_destination {
+                comm.append(&rv[..]);
+            }
             Ok(())
         }
         // Didn't consume the whole chunk; reset and error message.
         Err((None, _)) => {
             reset_parsers_state(states);
-            Err(io::StatusWords::Unknown.into())
+            Err(KadenaError::TrailingData.into())
         }
-        // Consumed the whole chunk and parser finished; send response.
-        Ok([]) => {
+        // Parser finished; reset, and send the response or the reason for rejecting the input.
+        Ok(rest) => {
             trace!("Parser finished, resetting state\n");
-            match parse_destination.as_ref() {
-                Some(rv) => comm.append(&rv[..]),
-                None => return Err(io::StatusWords::Unknown.into()),
-            }
-            // Parse finished; reset.
             reset_parsers_state(states);
-            Ok(())
+            match parse_destination {
+                // A rejection with a reason may end the parse anywhere in the chunk.
+                Some(Err(e)) => Err(e.into()),
+                Some(Ok(rv)) if rest.is_empty() => {
+                    comm.append(&rv[..]);
+                    Ok(())
+                }
+                // Parse ended before the chunk did.
+                Some(Ok(_)) => Err(KadenaError::TrailingData.into()),
+                None => Err(KadenaError::WrongParserState.into()),
+            }
         }
-        // Parse ended before the chunk did; reset.
-        Ok(_) => {
+    }
+}
+
+// The message in progress, and the index expected for its next chunk when it is sequenced
+struct ChunkSequence {
+    ins: Ins,
+    next_index: Option<u8>,
+}
+
+fn check_chunk(
+    sequence: &mut Option<ChunkSequence>,
+    states: &mut ParsersState,
+    ins: Ins,
+    chunk: Chunk,
+) -> Result<(), Reply> {
+    match chunk {
+        Chunk::Unsequenced => match sequence {
+            None => {
+                *sequence = Some(ChunkSequence {
+                    ins,
+                    next_index: None,
+                });
+                Ok(())
+            }
+            Some(s) if s.next_index.is_none() && s.ins == ins => Ok(()),
+            // An unsequenced message cannot be retried from where it was left, so a chunk of
+            // another command drops it, rather than being parsed as the start of a new one
+            Some(s) if s.next_index.is_none() => {
+                reset_parsers_state(states);
+                *sequence = None;
+                Err(KadenaError::OutOfOrderChunk.into())
+            }
+            Some(_) => Err(KadenaError::OutOfOrderChunk.into()),
+        },
+        Chunk::First { .. } => {
             reset_parsers_state(states);
-            Err(io::StatusWords::Unknown.into())
+            *sequence = Some(ChunkSequence {
+                ins,
+                next_index: Some(1),
+            });
+            Ok(())
         }
+        // A chunk which does not continue the message in progress leaves it untouched, so that
+        // the host can retry with the right one
+        Chunk::Continue { index, .. } => match sequence {
+            Some(ChunkSequence {
+                ins: s_ins,
+                next_index: Some(next_index),
+            }) if *s_ins == ins
+                && *next_index == index
+                && !matches!(states, ParsersState::NoState) =>
+            {
+                *next_index = index.wrapping_add(1);
+                Ok(())
+            }
+            _ => Err(KadenaError::OutOfOrderChunk.into()),
+        },
     }
 }
 
+#[inline(never)]
+fn handle_command(
+    comm: &mut io::Comm,
+    command: ApduCommand,
+    states: &mut ParsersState,
+    sequence: &mut Option<ChunkSequence>,
+    settings: &Settings,
+) -> Result<(), Reply> {
+    let ApduCommand { ins, chunk } = command;
+    // These are answered at once, and may come in between the chunks of a message
+    if let Ins::GetVersion | Ins::GetAppConfiguration | Ins::GetVersionStr | Ins::Exit = ins {
+        return handle_apdu(comm, ins, states, settings);
+    }
+    check_chunk(sequence, states, ins, chunk)?;
+    let rv = handle_apdu(comm, ins, states, settings);
+    if let ParsersState::NoState = states {
+        *sequence = None;
+    } else if chunk.is_last() {
+        // The last chunk has to complete the message
+        reset_parsers_state(states);
+        *sequence = None;
+        return Err(KadenaError::WrongParserState.into());
+    }
+    rv
+}
+
 #[inline(never)]
 fn handle_apdu(
     comm: &mut io::Comm,
@@ -165,42 +261,87 @@ fn handle_apdu(
             ]);
             comm.append(b"Kadena");
         }
-        Ins::VerifyAddress => run_parser_apdu::<_, Bip32Key>(
+        Ins::VerifyAddress => run_parser_apdu::<_, Bip32Key, 128>(
             parser,
             get_get_address_state::<true>,
             &get_address_impl::<true>(),
+            KadenaError::InvalidInput,
             comm,
         )?,
-        Ins::GetPubkey => run_parser_apdu::<_, Bip32Key>(
+        Ins::GetPubkey => run_parser_apdu::<_, Bip32Key, 128>(
             parser,
             get_get_address_state::<false>,
             &get_address_impl::<false>(),
+            KadenaError::InvalidInput,
+            comm,
+        )?,
+        Ins::Sign => run_parser_apdu::<_, SignParameters, 128>(
+            parser,
+            get_sign_state,
+            &SIGN_IMPL,
+            KadenaError::InvalidJson,
+            comm,
+        )?,
+        Ins::SignMulti => run_parser_apdu::<_, SignMultiParameters, SIGN_MULTI_RESPONSE_SIZE>(
+            parser,
+            get_sign_multi_state,
+            &SIGN_MULTI_IMPL,
+            KadenaError::InvalidJson,
+            comm,
+        )?,
+        Ins::SignForPath => run_parser_apdu::<_, SignForPathParameters, 128>(
+            parser,
+            get_sign_for_path_state,
+            &SIGN_FOR_PATH_IMPL,
+            KadenaError::InvalidJson,
             comm,
         )?,
-        Ins::Sign => {
-            run_parser_apdu::<_, SignParameters>(parser, get_sign_state, &SIGN_IMPL, comm)?
-        }
         Ins::SignHash => {
-            if settings.get() != 1 {
+            if !settings.blind_signing() {
                 write_scroller(false, "Blind Signing must", |w| {
                     Ok(write!(w, "be enabled")?)
                 });
-                return Err(io::SyscallError::NotSupported.into());
+                return Err(KadenaError::BlindSigningDisabled.into());
             } else {
-                run_parser_apdu::<_, SignHashParameters>(
+                run_parser_apdu::<_, SignHashParameters, 128>(
                     parser,
                     get_sign_hash_state,
                     &SIGN_HASH_IMPL,
+                    KadenaError::InvalidInput,
                     comm,
                 )?
             }
         }
-        Ins::MakeTransferTx => run_parser_apdu::<_, MakeTransferTxParameters>(
-            parser,
-            get_make_transfer_tx_state,
-            &MAKE_TRANSFER_TX_IMPL,
-            comm,
-        )?,
+        Ins::MakeTransferTx => {
+            run_parser_apdu::<_, MakeTransferTxParameters, MAKE_TRANSFER_TX_RESPONSE_SIZE>(
+                parser,
+                get_make_transfer_tx_state,
+                &MAKE_TRANSFER_TX_IMPL,
+                KadenaError::InvalidInput,
+                comm,
+            )?
+        }
+        Ins::GetAppConfiguration => {
+            let mut flags = 0;
+            if settings.blind_signing() {
+                flags |= CONFIG_BLIND_SIGNING_ENABLED;
+            }
+            if settings.expert_mode() {
+                flags |= CONFIG_EXPERT_MODE_ENABLED;
+            }
+            flags |= (settings.hash_display() as u8) << CONFIG_HASH_DISPLAY_SHIFT;
+            comm.append(&[flags]);
+            let mut supported = [0; 32];
+            for ins in 0..=u8::MAX {
+                if Ins::try_from(ins).is_ok() {
+                    supported[usize::from(ins / 8)] |= 1 << (ins % 8);
+                }
+            }
+            comm.append(&supported);
+            comm.append(&(ARG_ARRAY_SIZE as u16).to_le_bytes());
+            comm.append(&(CODE_ARRAY_SIZE as u16).to_le_bytes());
+            comm.append(&[MAX_SIGN_PATHS as u8, DEVICE_MODEL]);
+        }
         Ins::GetVersionStr => {
             comm.append(concat!("Kadena ", env!("CARGO_PKG_VERSION")).as_ref());
         }
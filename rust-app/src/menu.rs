@@ -22,9 +22,9 @@ pub enum IdleMenu {
     Exit,
 }
 
+// The entries of the settings submenu are those of SETTING_DESCRIPTORS, by index
 pub enum SettingsSubMenu {
-    EnableBlindSigning,
-    DisableBlindSigning,
+    Entry(usize),
     Back,
 }
 
@@ -45,13 +45,10 @@ impl Menu for IdleMenuWithSettings {
             ShowVersion => self.idle_menu = AppMain,
             Settings(None) => self.idle_menu = ShowVersion,
             Settings(Some(Back)) => {
-                if self.settings.get() == 1 {
-                    self.idle_menu = Settings(Some(DisableBlindSigning))
-                } else {
-                    self.idle_menu = Settings(Some(EnableBlindSigning))
-                }
+                self.idle_menu = Settings(Some(Entry(SETTING_DESCRIPTORS.len() - 1)))
             }
-            Settings(Some(_)) => self.idle_menu = Settings(Some(Back)),
+            Settings(Some(Entry(0))) => self.idle_menu = Settings(Some(Back)),
+            Settings(Some(Entry(i))) => self.idle_menu = Settings(Some(Entry(i - 1))),
             Exit => self.idle_menu = Settings(None),
         };
     }
@@ -62,14 +59,11 @@ impl Menu for IdleMenuWithSettings {
             AppMain => self.idle_menu = ShowVersion,
             ShowVersion => self.idle_menu = Settings(None),
             Settings(None) => self.idle_menu = Exit,
-            Settings(Some(Back)) => {
-                if self.settings.get() == 1 {
-                    self.idle_menu = Settings(Some(DisableBlindSigning))
-                } else {
-                    self.idle_menu = Settings(Some(EnableBlindSigning))
-                }
+            Settings(Some(Back)) => self.idle_menu = Settings(Some(Entry(0))),
+            Settings(Some(Entry(i))) if i + 1 < SETTING_DESCRIPTORS.len() => {
+                self.idle_menu = Settings(Some(Entry(i + 1)))
             }
-            Settings(Some(_)) => self.idle_menu = Settings(Some(Back)),
+            Settings(Some(Entry(_))) => self.idle_menu = Settings(Some(Back)),
             Exit => self.idle_menu = AppMain,
         };
     }
@@ -81,21 +75,15 @@ impl Menu for IdleMenuWithSettings {
             AppMain => None,
             ShowVersion => None,
             Settings(None) => {
-                if self.settings.get() == 1 {
-                    self.idle_menu = Settings(Some(DisableBlindSigning))
-                } else {
-                    self.idle_menu = Settings(Some(EnableBlindSigning))
-                };
-                None
-            }
-            Settings(Some(EnableBlindSigning)) => {
-                self.settings.set(&1);
-                self.idle_menu = Settings(Some(DisableBlindSigning));
+                self.idle_menu = Settings(Some(Entry(0)));
                 None
             }
-            Settings(Some(DisableBlindSigning)) => {
-                self.settings.set(&0);
-                self.idle_menu = Settings(Some(EnableBlindSigning));
+            // Cycle through the values of the setting
+            Settings(Some(Entry(i))) => {
+                let descriptor = &SETTING_DESCRIPTORS[i];
+                let next = (usize::from(self.settings.get(descriptor.setting)) + 1)
+                    % descriptor.values.len();
+                self.settings.set(descriptor.setting, next as u8);
                 None
             }
             Settings(Some(Back)) => {
@@ -131,20 +119,20 @@ impl Menu for IdleMenuWithSettings {
                     bold: true,
                 },
             ),
-            Settings(Some(EnableBlindSigning)) => (
-                MenuLabelTop::Text("Blind Signing"),
-                MenuLabelBottom {
-                    text: "Disabled",
-                    bold: false,
-                },
-            ),
-            Settings(Some(DisableBlindSigning)) => (
-                MenuLabelTop::Text("Blind Signing"),
-                MenuLabelBottom {
-                    text: "Enabled",
-                    bold: false,
-                },
-            ),
+            Settings(Some(Entry(i))) => {
+                let descriptor = &SETTING_DESCRIPTORS[i];
+                let value = usize::from(self.settings.get(descriptor.setting));
+                (
+                    MenuLabelTop::Text(descriptor.title),
+                    MenuLabelBottom {
+                        text: descriptor
+                            .values
+                            .get(value)
+                            .unwrap_or(&descriptor.values[0]),
+                        bold: false,
+                    },
+                )
+            }
             Settings(Some(Back)) => (
                 MenuLabelTop::Icon(&BACK_ICON),
                 MenuLabelBottom {
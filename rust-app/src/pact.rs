@@ -0,0 +1,217 @@
+// A small recogniser for the Pact code of the canonical fungible transfers, ie
+//
+// (coin.transfer "sender" "receiver" 1.0)
+// (coin.transfer-create "sender" "receiver" (read-keyset "ks") 1.0)
+// (coin.transfer-crosschain "sender" "receiver" (read-keyset "ks") "chain" 1.0)
+//
+// The code is taken as it appears in the JSON string, so the quotes may be escaped.
+
+use crate::decimal::Decimal;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TransferFunction {
+    Transfer,
+    TransferCreate,
+    TransferCrosschain,
+}
+
+#[derive(Debug)]
+pub struct TransferCode<'a> {
+    pub module: &'a str,
+    pub function: TransferFunction,
+    pub sender: &'a str,
+    pub receiver: &'a str,
+    pub keyset_name: Option<&'a str>,
+    pub target_chain: Option<&'a str>,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open,
+    Close,
+    Str(&'a str),
+    Atom(&'a str),
+}
+
+struct Lexer<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Lexer<'a> {
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.rest.as_bytes() {
+                [b' ' | b'\n' | b'\t' | b'\r', ..] => self.rest = &self.rest[1..],
+                [b'\\', b'n' | b't' | b'r', ..] => self.rest = &self.rest[2..],
+                _ => break,
+            }
+        }
+    }
+
+    // Strings must be closed by the same kind of quote that opened them, and escapes inside
+    // strings are not supported.
+    fn string(&mut self, quote: &str) -> Option<Token<'a>> {
+        let rest = &self.rest[quote.len()..];
+        let end = rest.find(|c: char| c == '"' || c == '\\')?;
+        if !rest[end..].starts_with(quote) {
+            return None;
+        }
+        self.rest = &rest[end + quote.len()..];
+        Some(Token::Str(&rest[..end]))
+    }
+
+    fn next_token(&mut self) -> Option<Token<'a>> {
+        self.skip_whitespace();
+        match self.rest.as_bytes() {
+            [] => None,
+            [b'(', ..] => {
+                self.rest = &self.rest[1..];
+                Some(Token::Open)
+            }
+            [b')', ..] => {
+                self.rest = &self.rest[1..];
+                Some(Token::Close)
+            }
+            [b'"', ..] => self.string("\""),
+            [b'\\', b'"', ..] => self.string("\\\""),
+            _ => {
+                let end = self
+                    .rest
+                    .find(|c: char| {
+                        c.is_ascii_whitespace() || c == '(' || c == ')' || c == '"' || c == '\\'
+                    })
+                    .unwrap_or(self.rest.len());
+                if end == 0 {
+                    return None;
+                }
+                let (atom, rest) = self.rest.split_at(end);
+                self.rest = rest;
+                Some(Token::Atom(atom))
+            }
+        }
+    }
+
+    fn expect(&mut self, token: Token<'a>) -> Option<()> {
+        if self.next_token()? == token {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn string_arg(&mut self) -> Option<&'a str> {
+        match self.next_token()? {
+            Token::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse_transfer_code(code: &str) -> Option<TransferCode<'_>> {
+    use TransferFunction::*;
+    let mut lexer = Lexer { rest: code };
+    lexer.expect(Token::Open)?;
+    let (module, function) = match lexer.next_token()? {
+        Token::Atom(name) => name.rsplit_once('.')?,
+        _ => return None,
+    };
+    let function = match function {
+        "transfer" => Transfer,
+        "transfer-create" => TransferCreate,
+        "transfer-crosschain" => TransferCrosschain,
+        _ => return None,
+    };
+    let sender = lexer.string_arg()?;
+    let receiver = lexer.string_arg()?;
+    let keyset_name = if function == Transfer {
+        None
+    } else {
+        lexer.expect(Token::Open)?;
+        lexer.expect(Token::Atom("read-keyset"))?;
+        let name = lexer.string_arg()?;
+        lexer.expect(Token::Close)?;
+        Some(name)
+    };
+    let target_chain = if function == TransferCrosschain {
+        Some(lexer.string_arg()?)
+    } else {
+        None
+    };
+    let amount = match lexer.next_token()? {
+        Token::Atom(a) => Decimal::parse_plain(a)?,
+        _ => return None,
+    };
+    lexer.expect(Token::Close)?;
+    if lexer.next_token().is_some() {
+        return None;
+    }
+    Some(TransferCode {
+        module,
+        function,
+        sender,
+        receiver,
+        keyset_name,
+        target_chain,
+        amount,
+    })
+}
+
+#[cfg(all(test, not(target_family = "bolos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_with_escaped_quotes() {
+        let t = parse_transfer_code(r#"(coin.transfer \"alice\" \"bob\" 1.0)"#).unwrap();
+        assert_eq!(t.module, "coin");
+        assert_eq!(t.function, TransferFunction::Transfer);
+        assert_eq!(t.sender, "alice");
+        assert_eq!(t.receiver, "bob");
+        assert_eq!(t.amount, Decimal::parse_plain("1").unwrap());
+        assert!(t.keyset_name.is_none());
+        assert!(t.target_chain.is_none());
+    }
+
+    #[test]
+    fn escape_inside_string() {
+        assert!(parse_transfer_code(r#"(coin.transfer "al\"ice" "bob" 1.0)"#).is_none());
+        assert!(parse_transfer_code(r#"(coin.transfer \"al\\\"ice\" \"bob\" 1.0)"#).is_none());
+    }
+
+    #[test]
+    fn escaped_whitespace() {
+        let t = parse_transfer_code(r#"\n(coin.transfer\n\t\"alice\"\r\n\"bob\" 1.0)\n"#).unwrap();
+        assert_eq!(t.sender, "alice");
+        assert_eq!(t.receiver, "bob");
+        assert!(parse_transfer_code(r#"(coin.transfer\x\"alice\" \"bob\" 1.0)"#).is_none());
+    }
+
+    #[test]
+    fn read_keyset() {
+        let t = parse_transfer_code(
+            r#"(coin.transfer-crosschain "alice" "bob" (read-keyset "ks") "2" 1.5)"#,
+        )
+        .unwrap();
+        assert_eq!(t.function, TransferFunction::TransferCrosschain);
+        assert_eq!(t.keyset_name, Some("ks"));
+        assert_eq!(t.target_chain, Some("2"));
+        assert!(
+            parse_transfer_code(r#"(coin.transfer-create "alice" "bob" (read-msg "ks") 1.0)"#)
+                .is_none()
+        );
+        assert!(parse_transfer_code(r#"(coin.transfer-create "alice" "bob" "ks" 1.0)"#).is_none());
+    }
+
+    #[test]
+    fn mismatched_quotes() {
+        assert!(parse_transfer_code(r#"(coin.transfer "alice\" \"bob" 1.0)"#).is_none());
+        assert!(parse_transfer_code(r#"(coin.transfer \"alice" "bob\" 1.0)"#).is_none());
+    }
+
+    #[test]
+    fn trailing_code() {
+        assert!(parse_transfer_code(r#"(coin.transfer "a" "b" 1.0) (coin.details "a")"#).is_none());
+        assert!(parse_transfer_code(r#"(coin.transfer "a" "b" 1.0"#).is_none());
+    }
+}
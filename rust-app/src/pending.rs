@@ -1,7 +1,7 @@
 use ledger_device_sdk::ui;
 use ledger_device_sdk::{buttons::ButtonEvent, io};
 
-use crate::interface::Ins;
+use crate::interface::ApduCommand;
 
 #[no_mangle]
 extern "C" fn sample_pending() {
@@ -9,14 +9,14 @@ extern "C" fn sample_pending() {
 
     loop {
         ui::SingleMessage::new("Pending").show();
-        match comm.next_event::<Ins>() {
+        match comm.next_event::<ApduCommand>() {
             io::Event::Button(ButtonEvent::RightButtonRelease) => break,
             _ => (),
         }
     }
     loop {
         ui::SingleMessage::new("Ledger review").show();
-        match comm.next_event::<Ins>() {
+        match comm.next_event::<ApduCommand>() {
             io::Event::Button(ButtonEvent::BothButtonsRelease) => break,
             _ => (),
         }
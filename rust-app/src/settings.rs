@@ -1,9 +1,76 @@
 use ledger_device_sdk::nvm::*;
 use ledger_device_sdk::NVMData;
 
+// The settings are stored as a version byte followed by the value of each setting, in the order
+// of the Setting enum. Unused bytes are kept for settings added later.
+const SETTINGS_VERSION: u8 = 2;
+const SETTINGS_SIZE: usize = 16;
+
+type StoredSettings = [u8; SETTINGS_SIZE];
+
 // This is necessary to store the object in NVM and not in RAM
 #[link_section = ".nvm_data"]
-static mut SETTINGS: NVMData<AtomicStorage<u8>> = NVMData::new(AtomicStorage::new(&0));
+static mut SETTINGS: NVMData<AtomicStorage<StoredSettings>> =
+    NVMData::new(AtomicStorage::new(&[0; SETTINGS_SIZE]));
+
+#[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    BlindSigning = 0,
+    ExpertMode = 1,
+    HashDisplay = 2,
+}
+
+// The value of a setting is the index of its label in `values`; the first is the default.
+pub struct SettingDescriptor {
+    pub setting: Setting,
+    pub title: &'static str,
+    pub values: &'static [&'static str],
+}
+
+// The entries of the settings menu, in the order they are shown
+pub const SETTING_DESCRIPTORS: &[SettingDescriptor] = &[
+    SettingDescriptor {
+        setting: Setting::BlindSigning,
+        title: "Blind Signing",
+        values: &["Disabled", "Enabled"],
+    },
+    SettingDescriptor {
+        setting: Setting::ExpertMode,
+        title: "Expert Mode",
+        values: &["Disabled", "Enabled"],
+    },
+    SettingDescriptor {
+        setting: Setting::HashDisplay,
+        title: "Hash Display",
+        values: &["Base64url", "Hex", "JSON: expert only"],
+    },
+];
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashDisplay {
+    Base64Url = 0,
+    Hex = 1,
+    // The hash of a JSON transaction is only shown in expert mode, in base64url; the hashes which
+    // are all there is to review are still always shown
+    JsonExpertOnly = 2,
+}
+
+fn default_settings() -> StoredSettings {
+    let mut stored = [0; SETTINGS_SIZE];
+    stored[0] = SETTINGS_VERSION;
+    stored
+}
+
+// Installing the app clears its NVM data, so there are no settings of earlier versions to carry
+// over: the storage is either a record of this version or zeroed, which is taken as the defaults
+fn migrate(stored: StoredSettings) -> StoredSettings {
+    match stored[0] {
+        SETTINGS_VERSION => stored,
+        _ => default_settings(),
+    }
+}
 
 #[derive(Clone, Copy)]
 pub struct Settings;
@@ -16,15 +83,38 @@ impl Default for Settings {
 
 impl Settings {
     #[inline(never)]
-    pub fn get(&self) -> u8 {
+    fn load(&self) -> StoredSettings {
         let settings = unsafe { SETTINGS.get_mut() };
-        return *settings.get_ref();
+        migrate(*settings.get_ref())
+    }
+
+    pub fn get(&self, setting: Setting) -> u8 {
+        self.load()[1 + setting as usize]
     }
 
     // The inline(never) is important. Otherwise weird segmentation faults happen on speculos.
     #[inline(never)]
-    pub fn set(&mut self, v: &u8) {
+    pub fn set(&mut self, setting: Setting, value: u8) {
+        let mut stored = self.load();
+        stored[1 + setting as usize] = value;
         let settings = unsafe { SETTINGS.get_mut() };
-        settings.update(v);
+        settings.update(&stored);
+    }
+
+    pub fn blind_signing(&self) -> bool {
+        self.get(Setting::BlindSigning) == 1
+    }
+
+    // Expert mode adds the nonce, the raw times and full keys and arguments to the review
+    pub fn expert_mode(&self) -> bool {
+        self.get(Setting::ExpertMode) == 1
+    }
+
+    pub fn hash_display(&self) -> HashDisplay {
+        match self.get(Setting::HashDisplay) {
+            1 => HashDisplay::Hex,
+            2 => HashDisplay::JsonExpertOnly,
+            _ => HashDisplay::Base64Url,
+        }
     }
 }
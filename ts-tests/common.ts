@@ -193,10 +193,39 @@ let toggleBlindSigningSettings = async function() {
   await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
   await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
   await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
+}
+
+let toggleExpertModeSettings = async function() {
+  await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
+}
+
+// The Hash Display setting cycles through base64url, hex and "JSON: expert only"
+let cycleHashDisplaySettings = async function(times: number) {
+  await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
+  await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
+  for (let i = 0; i < times; i++) {
+    await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
+  }
   await Axios.post(BASE_URL + "/button/right", {"action":"press-and-release"});
   await Axios.post(BASE_URL + "/button/both", {"action":"press-and-release"});
   await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
   await Axios.post(BASE_URL + "/button/left", {"action":"press-and-release"});
 }
 
-export { sendCommandAndAccept, BASE_URL, sendCommandExpectFail, toggleBlindSigningSettings }
+export { sendCommandAndAccept, BASE_URL, sendCommandExpectFail, toggleBlindSigningSettings, toggleExpertModeSettings, cycleHashDisplaySettings }
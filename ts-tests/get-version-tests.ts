@@ -11,4 +11,21 @@ describe("get version tests", function() {
       expect(rv.patch).to.equal(VERSION.patch);
       }, []);
     });
+  it("can get app configuration", async () => {
+    await sendCommandAndAccept(async (client : any) => {
+      const rv = await client.transport.send(0x00, 0x07, 0x00, 0x00, Buffer.alloc(0));
+      // Blind Signing is disabled by default
+      expect(rv[0]).to.equal(0x00);
+      let supported = Buffer.alloc(32);
+      supported[0] = 0xff; // GET_VERSION to GET_APP_CONFIGURATION
+      supported[2] = 0x01; // MAKE_TRANSFER_TX
+      supported[31] = 0xc0; // GET_VERSION_STR and QUIT_APP
+      expect(rv.slice(1, 33).toString('hex')).to.equal(supported.toString('hex'));
+      const nanos = rv[38] == 0;
+      expect(rv.readUInt16LE(33)).to.equal(nanos ? 184 : 2048);
+      expect(rv.readUInt16LE(35)).to.equal(nanos ? 256 : 1024);
+      expect(rv[37]).to.equal(2);
+      expect(rv.length).to.equal(39 + 2);
+      }, []);
+    });
 });
@@ -43,4 +43,66 @@ describe('public key tests', () => {
       },
     ]);
   });
+
+  it('rejects a path outside of the Kadena derivation with an invalid path status', async () => {
+
+    await sendCommandAndAccept(async (client : Kda) => {
+      let statusCode = null;
+      try {
+        await client.getPublicKey("44'/1'/0");
+      } catch (e : any) {
+        statusCode = e.statusCode;
+      }
+      expect(statusCode).to.equal(0xB001);
+    }, []);
+  });
+
+  // The path 44'/626'/0', as a count of steps followed by each step (little endian)
+  const sequencedPath = Buffer.from("032c0000807202008000000080", "hex");
+
+  it('provides a public key requested in sequenced chunks', async () => {
+
+    await sendCommandAndAccept(async (client : Kda) => {
+      const { publicKey } = await client.getPublicKey("44'/626'/0'");
+      const transport = (client as any).transport;
+      await transport.send(0x00, 0x02, 0x01, 0x00, sequencedPath.slice(0, 5));
+      const rv = await transport.send(0x00, 0x02, 0x06, 0x01, sequencedPath.slice(5));
+      expect(rv.slice(1, 33).toString('hex')).to.equal(new Buffer(publicKey).toString('hex'));
+    }, []);
+  });
+
+  it('rejects a chunk which does not continue the message in progress, and keeps the message', async () => {
+
+    await sendCommandAndAccept(async (client : Kda) => {
+      const { publicKey } = await client.getPublicKey("44'/626'/0'");
+      const transport = (client as any).transport;
+      await transport.send(0x00, 0x02, 0x01, 0x00, sequencedPath.slice(0, 5));
+      let statusCode = null;
+      try {
+        await transport.send(0x00, 0x02, 0x06, 0x02, sequencedPath.slice(5));
+      } catch (e : any) {
+        statusCode = e.statusCode;
+      }
+      expect(statusCode).to.equal(0xB006);
+      const rv = await transport.send(0x00, 0x02, 0x06, 0x01, sequencedPath.slice(5));
+      expect(rv.slice(1, 33).toString('hex')).to.equal(new Buffer(publicKey).toString('hex'));
+    }, []);
+  });
+
+  it('rejects an unsequenced chunk of another command in the middle of an unsequenced message, and drops the message', async () => {
+
+    await sendCommandAndAccept(async (client : Kda) => {
+      const transport = (client as any).transport;
+      await transport.send(0x00, 0x02, 0x00, 0x00, sequencedPath.slice(0, 5));
+      let statusCode = null;
+      try {
+        await transport.send(0x00, 0x04, 0x00, 0x00, sequencedPath.slice(5));
+      } catch (e : any) {
+        statusCode = e.statusCode;
+      }
+      expect(statusCode).to.equal(0xB006);
+      const rv = await client.getPublicKey("44'/626'/0");
+      expect(new Buffer(rv.publicKey).toString('hex')).to.equal("3f6f820616c6d999667deca91a0eccf25f62e2c910a4e77e811241445db888d7");
+    }, []);
+  });
 });
@@ -1,4 +1,4 @@
-import { VERSION, sendCommandAndAccept, BASE_URL, sendCommandExpectFail, toggleBlindSigningSettings } from "./common";
+import { VERSION, sendCommandAndAccept, BASE_URL, sendCommandExpectFail, toggleBlindSigningSettings, toggleExpertModeSettings, cycleHashDisplaySettings } from "./common";
 import { expect } from 'chai';
 import { describe, it, before, afterEach } from 'mocha';
 import Axios from 'axios';
@@ -11,6 +11,9 @@ let nacl : Nacl =null;
 
 instantiate(n => { nacl=n; });
 
+const WARNING_FOR_TRANSFER_MISMATCH = "The transfer made by the code does not match the transfer capabilities. The transaction may fail or do something other than the capabilities shown.";
+const WARNING_FOR_KEY_NOT_SIGNER = (address: string) => `The key for ${address} is not one of the signers of this transaction. Its signature does not grant any of the capabilities shown.`;
+
 function testTransaction(path: string, txn0: string, prompts: any[]) {
   return async () => {
     await sendCommandAndAccept(async (client : Kda) => {
@@ -29,6 +32,90 @@ function testTransaction(path: string, txn0: string, prompts: any[]) {
   }
 }
 
+// Runs the test with expert mode enabled, disabling it again afterwards
+function inExpertMode(test: () => Promise<void>) {
+  return async () => {
+    await toggleExpertModeSettings();
+    try {
+      await test();
+    } finally {
+      await toggleExpertModeSettings();
+    }
+  }
+}
+
+// Runs the test with the Hash Display setting cycled the given number of times from base64url,
+// cycling it back afterwards
+function withHashDisplay(times: number, test: () => Promise<void>) {
+  return async () => {
+    await cycleHashDisplaySettings(times);
+    try {
+      await test();
+    } finally {
+      await cycleHashDisplaySettings(3 - times);
+    }
+  }
+}
+
+function bip32KeyPayload(path: string): Buffer {
+  const steps = path.split("/").map(s => s.endsWith("'") ? (parseInt(s) + 0x80000000) >>> 0 : parseInt(s));
+  const payload = Buffer.alloc(1 + 4 * steps.length);
+  payload.writeUInt8(steps.length, 0);
+  steps.forEach((step, i) => payload.writeUInt32LE(step, 1 + 4 * i));
+  return payload;
+}
+
+// Uses the SIGN_JSON_TX_MULTI APDU, which returns a public key and signature for each path
+function testTransactionMultiPath(paths: string[], txn0: string, prompts: any[]) {
+  return async () => {
+    await sendCommandAndAccept(async (client : Kda) => {
+      const txn = Buffer.from(txn0, "utf-8");
+      let publicKeys = [];
+      for (const path of paths) {
+        const { publicKey } = await client.getPublicKey(path);
+        publicKeys.push(Buffer.from(publicKey));
+      }
+
+      // We don't want the prompts from getPublicKey in our result
+      await Axios.delete(BASE_URL + "/events");
+
+      const txnLength = Buffer.alloc(4);
+      txnLength.writeUInt32LE(txn.length);
+      const payload = Buffer.concat([txnLength, txn, Buffer.from([paths.length]), ...paths.map(bip32KeyPayload)]);
+      const rv : Buffer = await (client as any).sendChunks(0x00, 0x05, 0x00, 0x00, payload);
+      expect(rv.length).to.equal(96 * paths.length);
+      const hash = blake2b(32).update(txn).digest();
+      publicKeys.forEach((publicKey, i) => {
+        expect(rv.slice(96 * i, 96 * i + 32).toString("hex")).to.equal(publicKey.toString("hex"));
+        const pass = nacl.crypto_sign_verify_detached(rv.slice(96 * i + 32, 96 * (i + 1)), hash, publicKey);
+        expect(pass).to.equal(true);
+      });
+    }, prompts);
+  }
+}
+
+// Uses the SIGN_JSON_TX_FOR_PATH APDU, where the path comes before the transaction
+function testTransactionForPath(path: string, txn0: string, prompts: any[]) {
+  return async () => {
+    await sendCommandAndAccept(async (client : Kda) => {
+      const txn = Buffer.from(txn0, "utf-8");
+      const { publicKey } = await client.getPublicKey(path);
+
+      // We don't want the prompts from getPublicKey in our result
+      await Axios.delete(BASE_URL + "/events");
+
+      const txnLength = Buffer.alloc(4);
+      txnLength.writeUInt32LE(txn.length);
+      const payload = Buffer.concat([bip32KeyPayload(path), txnLength, txn]);
+      const sig : Buffer = await (client as any).sendChunks(0x00, 0x06, 0x00, 0x00, payload);
+      expect(sig.length).to.equal(64);
+      const hash = blake2b(32).update(txn).digest();
+      const pass = nacl.crypto_sign_verify_detached(sig, hash, publicKey);
+      expect(pass).to.equal(true);
+    }, prompts);
+  }
+}
+
 describe("Signing tests", function() {
   before( async function() {
     while(!nacl) await new Promise(r => setTimeout(r, 100));
@@ -41,14 +128,345 @@ describe("Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
          { "header": "Transaction hash", "prompt": "fPSCfMUaoK1N31qwhwBFUPwG-YR_guPP894uixsNZgk" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+
+  it("shows the transaction hash in hex",
+     withHashDisplay(1, testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "7cf4827cc51aa0ad4ddf5ab087004550fc06f9847f82e3cff3de2e8b1b0d6609" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     )));
+
+  it("hides the transaction hash outside of expert mode",
+     withHashDisplay(2, testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     )));
+
+  it("can sign a transfer of a fungible-v2 token",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(free.kdswap-token.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"free.kdswap-token.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(free.kdswap-token.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 free.kdswap-token from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "XJ00GqtXO5Y3K-lyFwY9QkN6VOFwabikJEUB0J1PTmw" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 free.kdswap-token" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("can sign a transfer of a token in a principal namespace",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(n_e309f0fa7cf3a13f93a8da5325cdad32790d2070.heron.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"n_e309f0fa7cf3a13f93a8da5325cdad32790d2070.heron.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(n_e309f0fa7cf3a13f93a8da5325cdad32790d2070.heron.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 n_e309f0fa7cf3a13f93a8da5325cdad32790d2070.heron from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "BNgi8cV77c6NPvdhNCmYkcYEf1_KOa30ATN0As9Th7o" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 n_e309f0fa7cf3a13f93a8da5325cdad32790d2070.heron" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+
+  it("shows the total sent by each sender",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","k:9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",1.5],"name":"coin.TRANSFER"},{"args":["k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","k:4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfa",2.25,"1"],"name":"coin.TRANSFER_XCHAIN"},{"args":["k:4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfa","k:9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",3],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "1.5 from \"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"k:9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "Transfer 2", "prompt": "Cross-chain 2.25 from \"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"k:4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfa\" to chain \"1\"" },
+         { "header": "Transfer 3", "prompt": "3 from \"k:4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfa\" to \"k:9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
+         { "header": "Transaction hash", "prompt": "9NlgVDHYfJuDcuGKMCvbdCr4AX45HsD214KijmcsA-0" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "3.75 KDA from k:83934c0f...0c471790" },
+         { "header": "Total Sending", "prompt": "3 KDA from k:4c310df6...ea572dfa" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("sums a transfer scoped by several signers only once",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(free.escrow.release \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]},{"pubKey":"8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2","clist":[{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(free.escrow.release \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "pJkXM-PXI-CmUjDdwJhIwItbb_-Sh2emLlhiNUARLmE" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Your Signer", "prompt": "2 of 2" },
+         { "header": "Total Sending", "prompt": "11 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("does not warn when co-signers scope the same transfer as the code",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]},{"pubKey":"8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2","clist":[{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "a1yGoBWs9L5e8rIH4MCsZFyfvw0b2SlCSDAW7kf9e1A" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Your Signer", "prompt": "2 of 2" },
+         { "header": "Total Sending", "prompt": "11 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("warns when a transfer has more decimal places than the token allows",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 1.0000000000001)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",1.0000000000001],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 1.0000000000001)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "1.0000000000001 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "WARNING", "prompt": "The amount has more decimal places than coin allows. The transfer will fail." },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "9BrvhXt_sXwNP31k3n_WeMKKfAR6snFw4oBuXyu0Jzo" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "1.0000000000001 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+
+  it("shows which signer is the key being signed with",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]},{"pubKey":"8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2","clist":[{"args":[],"name":"coin.GAS"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "0fPmZkVsoEAUrUJVaxcWiAas4wcHEO-2ucQQ3EsLYlU" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Your Signer", "prompt": "2 of 2" },
+         { "header": "Total Sending", "prompt": "11 KDA" },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -68,6 +486,7 @@ describe("Signing tests", function() {
           [
             { "header": "Signing", "prompt": "Transaction" },
             { "header": "On Network", "prompt": "mainnet01" },
+            { "header": "Code", "prompt": "(coin.transfer \"aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a\" \"4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfd\" 2.0)", "paginate": true },
             { "header": "Requiring", "prompt": "Capabilities" },
             { "header": "Of Key", "prompt": "aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a" },
             {
@@ -80,6 +499,10 @@ describe("Signing tests", function() {
             },
             { "header": "On Chain", "prompt": "0" },
             { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-6" },
+            { "header": "Max Fee", "prompt": "KDA 0.0006" },
+            { "header": "Gas Payer", "prompt": "aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a", "paginate": true },
+            { "header": "Valid For", "prompt": "15m" },
+            { "header": "Created At", "prompt": "2021-10-12 03:26:35 UTC" },
             {
               "header": "Transaction hash",
               "prompt": "anrl4cUVN53NFJCQ9tH4szt-ZzlCQ_SZuDI7e8OLyco",
@@ -88,6 +511,8 @@ describe("Signing tests", function() {
               "header": "Sign for Address",
               "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
             },
+            { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+            { "header": "Total Sending", "prompt": "2 KDA" },
             {
               "text": "Sign Transaction?",
               "x": 19,
@@ -99,20 +524,62 @@ describe("Signing tests", function() {
               "y": 11,
             }
           ]));
+  it("Shows warning when the maximum fee is unusually high",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":0.01,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 0.01" },
+         { "header": "Max Fee", "prompt": "KDA 6" },
+         { "header": "WARNING", "prompt": "The maximum fee of this transaction is unusually high. Check the gas limit and gas price." },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "07_-IAic2OEDqVaYbx6hTer-BEuEZvgAtcLF6_GvsRk" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
   it("can sign a simple transfer with network null",
      testTransaction(
        "44'/626'/0'",
        '{"networkId":null,"payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
        [
          { "header": "Signing", "prompt": "Transaction" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
          { "header": "Transaction hash", "prompt": "epv3lSVeZCWEYpPZet-ddYqpFSekJiIcw2azMb-Cn8w" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 KDA" },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -132,11 +599,12 @@ describe("Signing tests", function() {
           [
             { "header": "Signing", "prompt": "Transaction" },
             { "header": "On Network", "prompt": "mainnet01" },
+            { "header": "Code", "prompt": "(coin.transfer \"aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a\" \"4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfd\" 2.0)", "paginate": true },
             { "header": "Requiring", "prompt": "Capabilities" },
             { "header": "Of Key", "prompt": "aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a" },
             {
               "header": "Transfer 1",
-              "prompt": "{\"decimal\":\"123456789.0123456789\"} from \"aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a\" to \"4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfd\"",
+              "prompt": "123,456,789.0123456789 from \"aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a\" to \"4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfd\"",
             },
             {
               "header": "Paying Gas",
@@ -144,6 +612,11 @@ describe("Signing tests", function() {
             },
             { "header": "On Chain", "prompt": "0" },
             { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-6" },
+            { "header": "Max Fee", "prompt": "KDA 0.0006" },
+            { "header": "Gas Payer", "prompt": "aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a", "paginate": true },
+            { "header": "Valid For", "prompt": "15m" },
+            { "header": "Created At", "prompt": "2021-10-12 03:26:35 UTC" },
+            { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
             {
               "header": "Transaction hash",
               "prompt": "u4kRsc0DEmRbOOG2gePtMADMTOGGtRsXrMQ2R4bAvk4",
@@ -152,6 +625,8 @@ describe("Signing tests", function() {
               "header": "Sign for Address",
               "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
             },
+            { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+            { "header": "Total Sending", "prompt": "123,456,789.0123456789 KDA" },
             {
               "text": "Sign Transaction?",
               "x": 19,
@@ -170,6 +645,8 @@ describe("Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Keyset", "prompt": "recp-ks: keys-all [875e4493e19c8721583bfb46f0768f10266ebcca33c4a0e04bc099a7044a90f7]", "paginate": true },
+         { "header": "Code", "prompt": "(coin.transfer-create \"e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66\" \"875e4493e19c8721583bfb46f0768f10266ebcca33c4a0e04bc099a7044a90f7\" (read-keyset \"recp-ks\") 4.98340488)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66" },
          {
@@ -182,6 +659,10 @@ describe("Signing tests", function() {
          },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 60000 at price 1.0e-6" },
+         { "header": "Max Fee", "prompt": "KDA 0.06" },
+         { "header": "Gas Payer", "prompt": "e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:25:42 UTC" },
          {
            "header": "Transaction hash",
            "prompt": "SrjHkjfzLHLiOS-5_lcZvLOhiU42NynfAfezMzbeXsw",
@@ -190,6 +671,58 @@ describe("Signing tests", function() {
            "header": "Sign for Address",
            "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
          },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "4.98340488 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]));
+  it("Shows warning when the keyset of a k: account receiving a transfer-create is not its own key",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{"recp-ks":{"pred":"keys-all","keys":["d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093"]}},"code":"(coin.transfer-create \\"e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66\\" \\"k:875e4493e19c8721583bfb46f0768f10266ebcca33c4a0e04bc099a7044a90f7\\" (read-keyset \\"recp-ks\\") 4.98340488)"}},"signers":[{"pubKey":"e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66","clist":[{"args":[],"name":"coin.GAS"},{"args":["e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66","k:875e4493e19c8721583bfb46f0768f10266ebcca33c4a0e04bc099a7044a90f7",4.98340488],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009142,"ttl":28800,"gasLimit":60000,"chainId":"0","gasPrice":1.0e-6,"sender":"e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66"},"nonce":"\\"1634009156943\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Keyset", "prompt": "recp-ks: keys-all [d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093]", "paginate": true },
+         { "header": "Code", "prompt": "(coin.transfer-create \"e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66\" \"k:875e4493e19c8721583bfb46f0768f10266ebcca33c4a0e04bc099a7044a90f7\" (read-keyset \"recp-ks\") 4.98340488)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66" },
+         {
+           "header": "Paying Gas",
+           "prompt": " ",
+         },
+         {
+           "header": "Transfer 1",
+           "prompt": "4.98340488 from \"e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66\" to \"k:875e4493e19c8721583bfb46f0768f10266ebcca33c4a0e04bc099a7044a90f7\"",
+         },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 60000 at price 1.0e-6" },
+         { "header": "Max Fee", "prompt": "KDA 0.06" },
+         { "header": "Gas Payer", "prompt": "e4a1b2980c086c4551ab7d2148cf56e9774c64eb86f795d5fd83e39ccfd2ec66", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:25:42 UTC" },
+         {
+           "header": "WARNING",
+           "prompt": "The keyset of the k: account receiving the transfer is not its own key. The account may be controlled by someone else.",
+         },
+         {
+           "header": "Transaction hash",
+           "prompt": "q14UFtfkAz79as5sqnib7B6Rgips5Ro_p0UmBHmA528",
+         },
+         {
+           "header": "Sign for Address",
+           "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
+         },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "4.98340488 KDA" },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -210,6 +743,8 @@ describe("Signing tests", function() {
            "prompt": "Transaction"
          },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Keyset", "prompt": "recp-ks: keys-all [875e4493e19c8721583bfb46f0768f10266ebcca33c4a0e04bc099a7044a90f7]", "paginate": true },
+         { "header": "Code", "prompt": "(coin.transfer-create \"73580ffb3e5ca9859442395d4c1cb0bf3aa4e7246564ce943b7ae508b3ee7c03\" \"875e4493e19c8721583bfb46f0768f10266ebcca33c4a0e04bc099a7044a90f7\" (read-keyset \"recp-ks\") 4.89093455)", "paginate": true },
          {
            "header": "Requiring",
            "prompt": "Capabilities",
@@ -228,6 +763,10 @@ describe("Signing tests", function() {
          },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 60000 at price 1.0e-6" },
+         { "header": "Max Fee", "prompt": "KDA 0.06" },
+         { "header": "Gas Payer", "prompt": "73580ffb3e5ca9859442395d4c1cb0bf3aa4e7246564ce943b7ae508b3ee7c03", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:24:58 UTC" },
          {
            "header": "Transaction hash",
            "prompt": "pJsk0-vgbqfzOBFc4zHtFMSMa0aCZpXBZ_QQFxox1-k",
@@ -236,6 +775,8 @@ describe("Signing tests", function() {
            "header": "Sign for Address",
            "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
          },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "4.89093455 KDA" },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -255,6 +796,7 @@ describe("Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
@@ -264,8 +806,14 @@ describe("Signing tests", function() {
          },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "Transaction hash", "prompt": "FmmZBoFdyW_0T7oD1fXldK_MgKyvxTd4B3i7ew7VnMY" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -286,6 +834,8 @@ describe("Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Keyset", "prompt": "ks: keys-all [d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093]", "paginate": true },
+         { "header": "Code", "prompt": "(coin.rotate \"d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093\" (read-keyset \"ks\"))", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "81b4511b257fb975dace13e823c257c17ac6a695da65f91b6036d6e1429268fc" },
          {
@@ -298,6 +848,10 @@ describe("Signing tests", function() {
          },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 1500 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.015" },
+         { "header": "Gas Payer", "prompt": "81b4511b257fb975dace13e823c257c17ac6a695da65f91b6036d6e1429268fc", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-05 20:46:04 UTC" },
          {
            "header": "Transaction hash",
            "prompt": "WQImvdxCaI7U5Qy2U_3Mxoa3i-Lp-PyNu9aZNtXclHo",
@@ -306,6 +860,53 @@ describe("Signing tests", function() {
            "header": "Sign for Address",
            "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
          },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         },
+       ]));
+  it("warns about the keys of a keyset which are not ed25519 keys",
+     testTransaction(
+       "44'/626'/0'",
+'{"networkId":"mainnet01","payload":{"exec":{"data":{"ks":{"pred":"keys-all","keys":["d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093","WEBAUTHN-a4010103272006215820"]}},"code":"(coin.rotate \\"d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093\\" (read-keyset \\"ks\\"))"}},"signers":[{"pubKey":"81b4511b257fb975dace13e823c257c17ac6a695da65f91b6036d6e1429268fc","clist":[{"args":[],"name":"coin.GAS"},{"args":["d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093"],"name":"coin.ROTATE"}]}],"meta":{"creationTime":1633466764,"ttl":28800,"gasLimit":1500,"chainId":"0","gasPrice":1.0e-5,"sender":"81b4511b257fb975dace13e823c257c17ac6a695da65f91b6036d6e1429268fc"},"nonce":"\\"1633466764\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Keyset", "prompt": "ks: keys-all [d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093, and 1 other key]", "paginate": true },
+         { "header": "WARNING", "prompt": "The keyset has keys which are not ed25519 public keys. They cannot be shown on Ledger." },
+         { "header": "Code", "prompt": "(coin.rotate \"d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093\" (read-keyset \"ks\"))", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "81b4511b257fb975dace13e823c257c17ac6a695da65f91b6036d6e1429268fc" },
+         {
+           "header": "Paying Gas",
+           "prompt": " ",
+         },
+         {
+           "header": "Rotate for account",
+           "prompt": "\"d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093\"",
+         },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 1500 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.015" },
+         { "header": "Gas Payer", "prompt": "81b4511b257fb975dace13e823c257c17ac6a695da65f91b6036d6e1429268fc", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-05 20:46:04 UTC" },
+         {
+           "header": "Transaction hash",
+           "prompt": "f5MP4yckPzJRt0qHA7JMkYaMlJ3g1gkDezuiQmOpWXM",
+         },
+         {
+           "header": "Sign for Address",
+           "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
+         },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -324,6 +925,8 @@ describe("Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Keyset", "prompt": "ks: keys-all [d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093]", "paginate": true },
+         { "header": "Code", "prompt": "(coin.rotate \"d3300d284f4bcfbc91555184ef026a356e57ff0fa97b5e6c9830750892cd3093\" (read-keyset \"ks\"))", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "81b4511b257fb975dace13e823c257c17ac6a695da65f91b6036d6e1429268fc" },
          {
@@ -336,6 +939,10 @@ describe("Signing tests", function() {
          },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 1500 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.015" },
+         { "header": "Gas Payer", "prompt": "81b4511b257fb975dace13e823c257c17ac6a695da65f91b6036d6e1429268fc", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-05 20:46:04 UTC" },
          {
            "header": "Transaction hash",
            "prompt": "Rr78KvlVRiX59dDOqZFaK9vgW6GzgMss13p67yGOkN4",
@@ -344,6 +951,7 @@ describe("Signing tests", function() {
            "header": "Sign for Address",
            "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
          },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -365,6 +973,8 @@ describe("Signing tests", function() {
                 "prompt": "Transaction",
               },
               { "header": "On Network", "prompt": "testnet04" },
+              { "header": "Keyset", "prompt": "ks: keys-all [ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c]", "paginate": true },
+              { "header": "Code", "prompt": "(not-coin.transfer-crosschain \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" (read-keyset \"ks\") \"0\" 1.0)", "paginate": true },
               {
                 "header": "Requiring",
                 "prompt": "Capabilities",
@@ -379,6 +989,10 @@ describe("Signing tests", function() {
               },
               { "header": "On Chain", "prompt": "1" },
               { "header": "Using Gas", "prompt": "at most 600 at price 0.00001" },
+              { "header": "Max Fee", "prompt": "KDA 0.006" },
+              { "header": "Gas Payer", "prompt": "ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c", "paginate": true },
+              { "header": "Valid For", "prompt": "8h" },
+              { "header": "Created At", "prompt": "2021-12-23 20:11:07 UTC" },
               {
                 "header": "WARNING",
                 "prompt": "UNSAFE TRANSACTION. This transaction's code was not recognized and does not limit capabilities for all signers. Signing this transaction may make arbitrary actions on the chain including loss of all funds.",
@@ -391,6 +1005,7 @@ describe("Signing tests", function() {
                 "header": "Sign for Address",
                 "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
               },
+              { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
               {
                 "text": "Sign Transaction?",
                 "x": 19,
@@ -412,6 +1027,8 @@ describe("Signing tests", function() {
                 "prompt": "Transaction",
               },
               { "header": "On Network", "prompt": "testnet04" },
+              { "header": "Keyset", "prompt": "ks: keys-all [ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c]", "paginate": true },
+              { "header": "Code", "prompt": "(not-coin.transfer-crosschain \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" (read-keyset \"ks\") \"0\" 1.0)", "paginate": true },
               {
                 "header": "Requiring",
                 "prompt": "Capabilities",
@@ -426,6 +1043,10 @@ describe("Signing tests", function() {
               },
               { "header": "On Chain", "prompt": "1" },
               { "header": "Using Gas", "prompt": "at most 600 at price 0.00001" },
+              { "header": "Max Fee", "prompt": "KDA 0.006" },
+              { "header": "Gas Payer", "prompt": "ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c", "paginate": true },
+              { "header": "Valid For", "prompt": "8h" },
+              { "header": "Created At", "prompt": "2021-12-23 20:11:07 UTC" },
               {
                 "header": "WARNING",
                 "prompt": "UNSAFE TRANSACTION. This transaction's code was not recognized and does not limit capabilities for all signers. Signing this transaction may make arbitrary actions on the chain including loss of all funds.",
@@ -438,6 +1059,7 @@ describe("Signing tests", function() {
                 "header": "Sign for Address",
                 "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
               },
+              { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
               {
                 "text": "Sign Transaction?",
                 "x": 19,
@@ -459,6 +1081,8 @@ describe("Signing tests", function() {
                 "prompt": "Transaction",
               },
               { "header": "On Network", "prompt": "testnet04" },
+              { "header": "Keyset", "prompt": "ks: keys-all [dfdb3896919544490637c0fd2f34f8bf4463d416fbd915990c8a136b1a970ca5]", "paginate": true },
+              { "header": "Code", "prompt": "(coin.transfer-create \"k:b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164\" \"k:dfdb3896919544490637c0fd2f34f8bf4463d416fbd915990c8a136b1a970ca5\" (read-keyset \"ks\") 2.0)", "paginate": true },
               {
                 "header": "Requiring",
                 "prompt": "Capabilities",
@@ -477,6 +1101,10 @@ describe("Signing tests", function() {
               },
               { "header": "On Chain", "prompt": "1" },
               { "header": "Using Gas", "prompt": "at most 600 at price 0.00001" },
+              { "header": "Max Fee", "prompt": "KDA 0.006" },
+              { "header": "Gas Payer", "prompt": "k:b9ac3ca5...cca96164", "paginate": true },
+              { "header": "Valid For", "prompt": "8h" },
+              { "header": "Created At", "prompt": "2022-01-04 21:20:20 UTC" },
               {
                 "header": "Transaction hash",
                 "prompt": "9VlNQ6wmY5UpfOcazQNGpBZDt9Cd_sl_DO0POpiBDvU",
@@ -485,6 +1113,8 @@ describe("Signing tests", function() {
                 "header": "Sign for Address",
                 "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
               },
+              { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+              { "header": "Total Sending", "prompt": "2 KDA" },
               {
                 "text": "Sign Transaction?",
                 "x": 19,
@@ -497,6 +1127,42 @@ describe("Signing tests", function() {
               }
             ]
           ));
+  it("shows the nonce, the raw times and full accounts in expert mode",
+     inExpertMode(testTransaction(
+       "44'/626'/0'",
+       "{\"networkId\":\"testnet04\",\"payload\":{\"exec\":{\"data\":{\"ks\":{\"keys\":[\"dfdb3896919544490637c0fd2f34f8bf4463d416fbd915990c8a136b1a970ca5\"],\"pred\":\"keys-all\"}},\"code\":\"(coin.transfer-create \\\"k:b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164\\\" \\\"k:dfdb3896919544490637c0fd2f34f8bf4463d416fbd915990c8a136b1a970ca5\\\" (read-keyset \\\"ks\\\") 2.0)\"}},\"signers\":[{\"clist\":[{\"name\":\"coin.GAS\",\"args\":[]},{\"name\":\"coin.TRANSFER\",\"args\":[\"k:b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164\",\"k:dfdb3896919544490637c0fd2f34f8bf4463d416fbd915990c8a136b1a970ca5\",2]}],\"pubKey\":\"b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164\"}],\"meta\":{\"creationTime\":1641331220,\"ttl\":28800,\"gasLimit\":600,\"chainId\":\"1\",\"gasPrice\":0.00001,\"sender\":\"k:b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164\"},\"nonce\":\"\\\"\\\\\\\"2022-01-04T21:21:20.440Z\\\\\\\"\\\"\"}",
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "testnet04" },
+         { "header": "Keyset", "prompt": "ks: keys-all [dfdb3896919544490637c0fd2f34f8bf4463d416fbd915990c8a136b1a970ca5]", "paginate": true },
+         { "header": "Code", "prompt": "(coin.transfer-create \"k:b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164\" \"k:dfdb3896919544490637c0fd2f34f8bf4463d416fbd915990c8a136b1a970ca5\" (read-keyset \"ks\") 2.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "2 from \"k:b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164\" to \"k:dfdb3896919544490637c0fd2f34f8bf4463d416fbd915990c8a136b1a970ca5\"" },
+         { "header": "Of Key", "prompt": "b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164" },
+         { "header": "On Chain", "prompt": "1" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 0.00001" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "k:b9ac3ca5559cc6f394ea0e31c11be16efd6c6ff6804b98ce7cee496bcca96164", "paginate": true },
+         { "header": "Valid For", "prompt": "8h (28800 seconds)" },
+         { "header": "Created At", "prompt": "2022-01-04 21:20:20 UTC (1641331220)" },
+         { "header": "Nonce", "prompt": "\"\\\"2022-01-04T21:21:20.440Z\\\"\"" },
+         { "header": "Transaction hash", "prompt": "9VlNQ6wmY5UpfOcazQNGpBZDt9Cd_sl_DO0POpiBDvU" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "2 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     )));
   it("Shows custom message for basic cross-chain transfers.",
           testTransaction(
             "44'/626'/0'", "{\"networkId\":\"testnet04\",\"payload\":{\"exec\":{\"data\":{\"ks\":{\"keys\":[\"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\"],\"pred\":\"keys-all\"}},\"code\":\"(coin.transfer-crosschain \\\"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\\\" \\\"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\\\" (read-keyset \\\"ks\\\") \\\"0\\\" 1.0)\"}},\"signers\":[{\"pubKey\":\"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\",\"clist\":[{\"name\":\"coin.GAS\",\"args\":[]},{\"name\":\"coin.TRANSFER_XCHAIN\",\"args\":[\"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\",\"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\",1.0,\"0\"]}]}],\"meta\":{\"creationTime\":1640290267,\"ttl\":28800,\"gasLimit\":600,\"chainId\":\"1\",\"gasPrice\":0.00001,\"sender\":\"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\"},\"nonce\":\"\\\"\\\\\\\"2021-12-23T20:12:06.664Z\\\\\\\"\\\"\"}",
@@ -506,6 +1172,8 @@ describe("Signing tests", function() {
                 "prompt": "Transaction",
               },
               { "header": "On Network", "prompt": "testnet04" },
+              { "header": "Keyset", "prompt": "ks: keys-all [ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c]", "paginate": true },
+              { "header": "Code", "prompt": "(coin.transfer-crosschain \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" (read-keyset \"ks\") \"0\" 1.0)", "paginate": true },
               {
                 "header": "Requiring",
                 "prompt": "Capabilities",
@@ -520,10 +1188,15 @@ describe("Signing tests", function() {
               },
               {
                 "header": "Transfer 1",
-                "prompt": "Cross-chain 1.0 from \"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" to \"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" to chain \"0\"",
+                "prompt": "Cross-chain 1 from \"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" to \"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" to chain \"0\"",
               },
               { "header": "On Chain", "prompt": "1" },
               { "header": "Using Gas", "prompt": "at most 600 at price 0.00001" },
+              { "header": "Max Fee", "prompt": "KDA 0.006" },
+              { "header": "Gas Payer", "prompt": "ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c", "paginate": true },
+              { "header": "Valid For", "prompt": "8h" },
+              { "header": "Created At", "prompt": "2021-12-23 20:11:07 UTC" },
+              { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
               {
                 "header": "Transaction hash",
                 "prompt": "nw3YtHZ5EgogG2oQ9JbOOEqyhy7IN4cevGjdEKuWgQM",
@@ -532,6 +1205,8 @@ describe("Signing tests", function() {
                 "header": "Sign for Address",
                 "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
               },
+              { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+              { "header": "Total Sending", "prompt": "1 KDA" },
               {
                 "text": "Sign Transaction?",
                 "x": 19,
@@ -553,6 +1228,8 @@ describe("Signing tests", function() {
                 "prompt": "Transaction",
               },
               { "header": "On Network", "prompt": "testnet04" },
+              { "header": "Keyset", "prompt": "ks: keys-all [ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c]", "paginate": true },
+              { "header": "Code", "prompt": "(coin.transfer-crosschain \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" (read-keyset \"ks\") \"0\" 1.0)", "paginate": true },
               {
                 "header": "Requiring",
                 "prompt": "Capabilities",
@@ -567,10 +1244,15 @@ describe("Signing tests", function() {
               },
               {
                 "header": "Transfer 1",
-                "prompt": "Cross-chain {\"decimal\":\"123456789.0123456789\"} from \"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" to \"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" to chain \"0\"",
+                "prompt": "Cross-chain 123,456,789.0123456789 from \"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" to \"k:ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" to chain \"0\"",
               },
               { "header": "On Chain", "prompt": "1" },
               { "header": "Using Gas", "prompt": "at most 600 at price 0.00001" },
+              { "header": "Max Fee", "prompt": "KDA 0.006" },
+              { "header": "Gas Payer", "prompt": "ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c", "paginate": true },
+              { "header": "Valid For", "prompt": "8h" },
+              { "header": "Created At", "prompt": "2021-12-23 20:11:07 UTC" },
+              { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
               {
                 "header": "Transaction hash",
                 "prompt": "gaYu1-LR6N9V0bUt1u_N9p4cbm_dwy7IeHC52rD92gs",
@@ -579,6 +1261,8 @@ describe("Signing tests", function() {
                 "header": "Sign for Address",
                 "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
               },
+              { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+              { "header": "Total Sending", "prompt": "123,456,789.0123456789 KDA" },
               {
                 "text": "Sign Transaction?",
                 "x": 19,
@@ -600,6 +1284,8 @@ describe("Signing tests", function() {
                 "prompt": "Transaction",
               },
               { "header": "On Network", "prompt": "testnet04" },
+              { "header": "Keyset", "prompt": "ks: keys-all [ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c]", "paginate": true },
+              { "header": "Code", "prompt": "(coin.transfer-crosschain \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" \"ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c\" (read-keyset \"ks\") \"0\" 1.0)", "paginate": true },
               {
                 "header": "Requiring",
                 "prompt": "Capabilities",
@@ -618,6 +1304,11 @@ describe("Signing tests", function() {
               },
               { "header": "On Chain", "prompt": "1" },
               { "header": "Using Gas", "prompt": "at most 600 at price 0.00001" },
+              { "header": "Max Fee", "prompt": "KDA 0.006" },
+              { "header": "Gas Payer", "prompt": "ffd8cd79deb956fa3c7d9be0f836f20ac84b140168a087a842be4760e40e2b1c", "paginate": true },
+              { "header": "Valid For", "prompt": "8h" },
+              { "header": "Created At", "prompt": "2021-12-23 20:11:07 UTC" },
+              { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
               {
                 "header": "Transaction hash",
                 "prompt": "LY8HM_kQ2nRO7Wl0PD9flhbibi0K1CXxv27KmlDBQmo",
@@ -626,6 +1317,7 @@ describe("Signing tests", function() {
                 "header": "Sign for Address",
                 "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
               },
+              { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
               {
                 "text": "Sign Transaction?",
                 "x": 19,
@@ -645,6 +1337,7 @@ describe("Signing tests", function() {
           [
             { "header": "Signing", "prompt": "Transaction" },
             { "header": "On Network", "prompt": "mainnet01" },
+            { "header": "Code", "prompt": "(coin.transfer \"aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a\" \"4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfd\" 2.0)", "paginate": true },
             { "header": "Requiring", "prompt": "Capabilities" },
             { "header": "Of Key", "prompt": "aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a" },
             {
@@ -669,6 +1362,11 @@ describe("Signing tests", function() {
             },
             { "header": "On Chain", "prompt": "0" },
             { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-6" },
+            { "header": "Max Fee", "prompt": "KDA 0.0006" },
+            { "header": "Gas Payer", "prompt": "aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a", "paginate": true },
+            { "header": "Valid For", "prompt": "15m" },
+            { "header": "Created At", "prompt": "2021-10-12 03:26:35 UTC" },
+            { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
             {
               "header": "Transaction hash",
               "prompt": "cYmajadc0EPG3ifvKR1Yd_-wlG79UZirK47JOREfZhk",
@@ -677,6 +1375,8 @@ describe("Signing tests", function() {
               "header": "Sign for Address",
               "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
             },
+            { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+            { "header": "Total Sending", "prompt": "10 KDA" },
             {
               "text": "Sign Transaction?",
               "x": 19,
@@ -695,6 +1395,7 @@ describe("Signing tests", function() {
           [
             { "header": "Signing", "prompt": "Transaction" },
             { "header": "On Network", "prompt": "mainnet01" },
+            { "header": "Code", "prompt": "(coin.transfer \"aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a\" \"4c310df6224d674d80463a29cde00cb0ecfb71e0cfdce494243a61b8ea572dfd\" 2.0)", "paginate": true },
             { "header": "Requiring", "prompt": "Capabilities" },
             { "header": "Of Key", "prompt": "aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a" },
             {
@@ -719,6 +1420,11 @@ describe("Signing tests", function() {
             },
             { "header": "On Chain", "prompt": "0" },
             { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-6" },
+            { "header": "Max Fee", "prompt": "KDA 0.0006" },
+            { "header": "Gas Payer", "prompt": "aab7d3e457f3f78480832d6ac4ace7387f460620a63a5b68c8c799d6bff1566a", "paginate": true },
+            { "header": "Valid For", "prompt": "15m" },
+            { "header": "Created At", "prompt": "2021-10-12 03:26:35 UTC" },
+            { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
             {
               "header": "Transaction hash",
               "prompt": "AoXqSSMScM_u4glsmLV3C8Eawexbm2YEFgFMHYFzm4o",
@@ -727,6 +1433,8 @@ describe("Signing tests", function() {
               "header": "Sign for Address",
               "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2",
             },
+            { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+            { "header": "Total Sending", "prompt": "10 KDA" },
             {
               "text": "Sign Transaction?",
               "x": 19,
@@ -741,20 +1449,261 @@ describe("Signing tests", function() {
   it("can sign a simple transfer with unknown meta",
      testTransaction(
        "44'/626'/0'",
-       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"unknown-field":true,"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"unknown-field":true,"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         {
+           "header": "CAUTION",
+           "prompt": "'meta' field of transaction not recognized",
+         },
+         { "header": "Transaction hash", "prompt": "fysHQicr1iPz-sbSntIM3Rx_Iw_agBhRxt-XL9X7ENk" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("shows a creation time which is too long to display",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":16340092140000000000000,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "cannot be displayed on Ledger" },
+         { "header": "Transaction hash", "prompt": "uZ4cDXkcgkkFjVxDIxnVd8iPYADfgez3U-qAWfLnnSc" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 KDA" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("Shows the start and hash of code which is too large to display",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\") (coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\")"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.details \"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\") (coin.details \"k:83934c0f9b005f378ba3520f...", "paginate": true },
+         { "header": "Code hash", "prompt": "C_qIZWhCkvZs-7qZ54uFg314VVqiD69YAq1aMHg9vV0" },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": "Transaction too large for Ledger to display.  PROCEED WITH GREAT CAUTION.  Do you want to continue?" },
+         { "header": "Transaction hash", "prompt": "MdBKnOhnZNCsiXfGEeiqCviokYLqxE2Yf6smj3W5iKo" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("decodes the escapes in the code it shows",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.details \\u0022k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\u0022\\u0029\\n"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.details \"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\") ", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "4KM-svhlbUwqGDXcdibEXuReSIDU9hUPraRQWxbVqqc" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("shows the start and hash of code with a character which cannot be shown",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.details \\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\")\\u0000"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.details \"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\")...", "paginate": true },
+         { "header": "Code hash", "prompt": "vaUih759qVlQl9eWBwVIlIL7nQC32x_dvV3SSA3zeB0" },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": "Transaction too large for Ledger to display.  PROCEED WITH GREAT CAUTION.  Do you want to continue?" },
+         { "header": "Transaction hash", "prompt": "6L6gya7oXGTZj0YGycJ_GoUmbdjCrtgGdrdO4VUx7oA" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("can sign a continuation of a cross-chain transfer",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"testnet04","payload":{"cont":{"proof":"eyJjaGFpbiI6MSwib2JqZWN0IjoiQUFBQUVBQUFBQUFBQUFBQiJ9","pactId":"ZJ6nf3rnPdFmkkAm_vGDjF3uBhpLQH8A2ZMHwS-Dv3I","rollback":false,"step":1,"data":{}}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":850,"chainId":"1","gasPrice":1.0e-8,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
        [
          { "header": "Signing", "prompt": "Transaction" },
-         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "On Network", "prompt": "testnet04" },
+         { "header": "Continuing Pact", "prompt": "ZJ6nf3rnPdFmkkAm_vGDjF3uBhpLQH8A2ZMHwS-Dv3I", "paginate": true },
+         { "header": "Step", "prompt": "1" },
+         { "header": "Rollback", "prompt": "No" },
+         { "header": "SPV Proof", "prompt": "Attached" },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
-         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "1" },
+         { "header": "Using Gas", "prompt": "at most 850 at price 1.0e-8" },
+         { "header": "Max Fee", "prompt": "KDA 0.0000085" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "fbq5HDfsR94kyI6rAFGeE3tdg9-rufL1svYgJVmiGXE" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
-           "header": "CAUTION",
-           "prompt": "'meta' field of transaction not recognized",
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
          },
-         { "header": "Transaction hash", "prompt": "fysHQicr1iPz-sbSntIM3Rx_Iw_agBhRxt-XL9X7ENk" },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("only shows the signer for the path when it is given first",
+     testTransactionForPath(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]},{"pubKey":"8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2","clist":[{"args":[],"name":"coin.GAS"}]},{"pubKey":"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42","clist":[{"args":["9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42","83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790",1],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Your Key", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Also Signing", "prompt": "2 other signers" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "jNidI0UP8hacAE7Y7e8br9m4d3wd8QxwDlDHoawqfeE" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Your Signer", "prompt": "2 of 3" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("can sign a transaction with multiple paths",
+     testTransactionMultiPath(
+       ["44'/626'/0'", "44'/626'/1'"],
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11],"name":"coin.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Transfer 1", "prompt": "11 from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "fPSCfMUaoK1N31qwhwBFUPwG-YR_guPP894uixsNZgk" },
+         { "header": "Sign for Address 1", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "Sign for Address 2", "prompt": "fd0a79f794fbe157c78a63f9e6e9edeb90995cf0faf794ddb3c09400d26c4b0d" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("Address 1") },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("Address 2") },
+         { "header": "Total Sending", "prompt": "11 KDA" },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -883,14 +1832,21 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Unknown Capability 1", "prompt": "name: mycoin.MY_TRANSFER, no args" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "Transaction hash", "prompt": "hnaoFEVgtSMrwKbm2Ui4wnARtUwMo6rtB3fnvZGb8oE" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -911,14 +1867,21 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Unknown Capability 1", "prompt": "name: mycoin.MY_TRANSFER, arg 1: \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\"" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "Transaction hash", "prompt": "kQqVYwYzDNSKqcRwJ3Yd4xgG2UW9j2sdcupQx-T6XEY" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -939,14 +1902,21 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Unknown Capability 1", "prompt": "name: mycoin.MY_TRANSFER, arg 1: \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\", arg 2: \"second arg\"" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "Transaction hash", "prompt": "ONXn9kz2V9InGB-RddO3kUCy-GHQOEs8jRYqO2vzxuY" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -967,14 +1937,21 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Unknown Capability 1", "prompt": "name: mycoin.MY_TRANSFER, arg 1: \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\", arg 2: \"second arg\", arg 3: 22.2" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "Transaction hash", "prompt": "OEV1W2Adz7vvU3qYzV9V48pDhxRdFDi2KG4JXx73WTA" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -995,14 +1972,21 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Unknown Capability 1", "prompt": "name: mycoin.MY_TRANSFER, arg 1: {\"key1\":{\"key2\":\"val2\"},\"key3\":-2.46,\"key4\":{\"key5\":true,\"key6\":{\"key7\":0.01},\"key8\":[\"a\",false,null,9,10.23,-58.24]}}, arg 2: {}, arg 3: [], arg 4: false, arg 5: null" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "Transaction hash", "prompt": "5RygRqoczKtecEebMtaPLrulHa5aprNcjkRhMAAogNc" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -1023,6 +2007,7 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
@@ -1033,8 +2018,124 @@ describe("Capability Signing tests", function() {
          { "header": "Unknown Capability 5", "prompt": "name: mycoin.MY_TRANSFER4, arg 1: \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\", arg 2: \"second arg\", arg 3: 5000, arg 4: 22.2" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "Transaction hash", "prompt": "QJDO0ks635Xpnq2GC85cqoQUxLgESujMgun7NUgrf5E" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+
+  it("shows the arguments of well-known caps with their labels",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(coin.transfer \\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\" \\"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\\" 11.0)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790",600,1.0e-5],"name":"util.guards.GAS_PAYER"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"],"name":"coin.DEBIT"},{"args":["t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU","alice","bob",1.0,"UIH1VjVvAw1pDrbSY6gyhW1tkOQpYLJRB3tYDxN9nXY"],"name":"marmalade-v2.ledger.BUY"},{"args":["83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42",11.0],"name":"free.kdswap-token.TRANSFER"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Gas Payer Station", "prompt": "user: 83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790, limit: 600, price: 0.00001" },
+         { "header": "Debit", "prompt": "account: 83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Token Buy", "prompt": "token: t:Pbn1XKhC...bBsObyEU, seller: alice, buyer: bob, amount: 1, sale: UIH1VjVvAw1pDrbSY6gyhW1tkOQpYLJRB3tYDxN9nXY" },
+         { "header": "Transfer 1", "prompt": "11 free.kdswap-token from \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" to \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\"" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
+         { "header": "Transaction hash", "prompt": "6Z0cYejbM15-XSbs8Ccmt84FTLTR0DuWmFYmZph1tZw" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "11 free.kdswap-token" },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+
+  it("shows the Marmalade sale caps, with a warning for an invalid timeout",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(marmalade-v2.ledger.sale \\"t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU\\" \\"alice\\" 1.0 4500000)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU","alice",1.0,1700000000],"name":"marmalade-v2.ledger.OFFER"},{"args":["t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU","alice",1.0,{"int":4500000},"UIH1VjVvAw1pDrbSY6gyhW1tkOQpYLJRB3tYDxN9nXY"],"name":"marmalade-v2.ledger.SALE"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(marmalade-v2.ledger.sale \"t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU\" \"alice\" 1.0 4500000)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Token Offer", "prompt": "token: t:Pbn1XKhC...bBsObyEU, seller: alice, amount: 1, timeout: 2023-11-14 22:13:20 UTC" },
+         { "header": "Token Sale", "prompt": "token: t:Pbn1XKhC...bBsObyEU, seller: alice, amount: 1, timeout: 1970-02-22 02:00:00 UTC, sale: UIH1VjVvAw1pDrbSY6gyhW1tkOQpYLJRB3tYDxN9nXY" },
+         { "header": "WARNING", "prompt": "The timeout of this capability is not a valid time or block height. The sale may expire at once or never." },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "Transaction hash", "prompt": "8Ezbevd9uyP-xiHtjVqurNJQCnOj1WmSa2Cm4YwSFnM" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         {
+           "text": "Sign Transaction?",
+           "x": 19,
+           "y": 11,
+         },
+         {
+           "text": "Confirm",
+           "x": 43,
+           "y": 11,
+         }
+       ]
+     ));
+  it("shows the Marmalade sale caps, with a warning for a timeout before the creation time",
+     testTransaction(
+       "44'/626'/0'",
+       '{"networkId":"mainnet01","payload":{"exec":{"data":{},"code":"(marmalade-v2.ledger.sale \\"t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU\\" \\"alice\\" 1.0 4500000)"}},"signers":[{"pubKey":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790","clist":[{"args":[],"name":"coin.GAS"},{"args":["t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU","alice",1.0,1634000000],"name":"marmalade-v2.ledger.OFFER"},{"args":["t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU","alice",1.0,{"int":1700000000},"UIH1VjVvAw1pDrbSY6gyhW1tkOQpYLJRB3tYDxN9nXY"],"name":"marmalade-v2.ledger.SALE"}]}],"meta":{"creationTime":1634009214,"ttl":28800,"gasLimit":600,"chainId":"0","gasPrice":1.0e-5,"sender":"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"},"nonce":"\\"2021-10-12T03:27:53.700Z\\""}',
+       [
+         { "header": "Signing", "prompt": "Transaction" },
+         { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(marmalade-v2.ledger.sale \"t:Pbn1XKhCjGqD6kx-N0VEiLG5Vk7XWZxo6GdbBsObyEU\" \"alice\" 1.0 4500000)", "paginate": true },
+         { "header": "Requiring", "prompt": "Capabilities" },
+         { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
+         { "header": "Paying Gas", "prompt": " " },
+         { "header": "Token Offer", "prompt": "token: t:Pbn1XKhC...bBsObyEU, seller: alice, amount: 1, timeout: 2021-10-12 00:53:20 UTC" },
+         { "header": "Token Sale", "prompt": "token: t:Pbn1XKhC...bBsObyEU, seller: alice, amount: 1, timeout: 2023-11-14 22:13:20 UTC, sale: UIH1VjVvAw1pDrbSY6gyhW1tkOQpYLJRB3tYDxN9nXY" },
+         { "header": "On Chain", "prompt": "0" },
+         { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": "A timeout of the capabilities is not after the creation time of the transaction. The sale has already expired." },
+         { "header": "Transaction hash", "prompt": "d7fUwmt9pm2FzFz4Mzuu8Ngo6Q529Iqb3sJM6b1RcqY" },
+         { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -1055,6 +2156,7 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
@@ -1065,8 +2167,15 @@ describe("Capability Signing tests", function() {
          { "header": "Unknown Capability 3", "prompt": "name: mycoin.MY_TRANSFER4, arg 1: \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\", arg 2: \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471792\", arg 3: 5000, arg 4: \"0\"" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "Transaction hash", "prompt": "yMXcVG1vcnLrbtdiKHI1MAYgrBgoDqr15YSRID70DyU" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
+         { "header": "Total Sending", "prompt": "26.2 KDA" },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -1086,15 +2195,22 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Unknown Capability 1", "prompt": "name: mycoin.MY_TRANSFER, args cannot be displayed on Ledger" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "WARNING", "prompt": WARNING_FOR_CAP_NOT_SHOWN },
          { "header": "Transaction hash", "prompt": "Y2q38WX4sd5fWzw2knr7mfAltsaYxhWnDGtFaZ7NV40" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -1116,15 +2232,22 @@ describe("Capability Signing tests", function() {
        [
          { "header": "Signing", "prompt": "Transaction" },
          { "header": "On Network", "prompt": "mainnet01" },
+         { "header": "Code", "prompt": "(coin.transfer \"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\" \"9790d119589a26114e1a42d92598b3f632551c566819ec48e0e8c54dae6ebb42\" 11.0)", "paginate": true },
          { "header": "Requiring", "prompt": "Capabilities" },
          { "header": "Of Key", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" },
          { "header": "Paying Gas", "prompt": " " },
          { "header": "Unknown Capability 1", "prompt": "name: marmalade.ledger.transfer, args cannot be displayed on Ledger" },
          { "header": "On Chain", "prompt": "0" },
          { "header": "Using Gas", "prompt": "at most 600 at price 1.0e-5" },
+         { "header": "Max Fee", "prompt": "KDA 0.006" },
+         { "header": "Gas Payer", "prompt": "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Valid For", "prompt": "8h" },
+         { "header": "Created At", "prompt": "2021-10-12 03:26:54 UTC" },
+         { "header": "WARNING", "prompt": WARNING_FOR_TRANSFER_MISMATCH },
          { "header": "WARNING", "prompt": WARNING_FOR_CAP_NOT_SHOWN },
          { "header": "Transaction hash", "prompt": "TX4rKze978k7T-MAzSJfTTHy1WCwAK8yi4RhZfAQzQE" },
          { "header": "Sign for Address", "prompt": "8d5d63bb1071a8dfc5c09ac96cfa50341a74eb91b6ea9ee5724cde09ef758bf2" },
+         { "header": "WARNING", "prompt": WARNING_FOR_KEY_NOT_SIGNER("this address") },
          {
            "text": "Sign Transaction?",
            "x": 19,
@@ -1179,6 +2302,118 @@ function checkSignTransferTxAPIs(apiName: any,
   }
 }
 
+function checkSignTransferTxFail(apiName: any, params: any) {
+  return async () => {
+    await sendCommandExpectFail(
+      async (client : Kda) => {
+        await client[apiName](params);
+      });
+  }
+}
+
+// hw-app-kda only builds transfers from the k: account of the key, so the MAKE_TRANSFER_TX
+// payload for other senders is built here
+function makeTransferTxPayload(params: any): Buffer {
+  const str = (s: string) => Buffer.concat([Buffer.from([Buffer.byteLength(s)]), Buffer.from(s, "utf-8")]);
+  const sender = params.sender === undefined ? [] : [str(params.sender)];
+  const recipientKind = params.recipientKind === undefined ? [] : [Buffer.from([params.recipientKind])];
+  const guard = params.guard === undefined ? [] : [
+    str(params.guard.pred),
+    Buffer.from([params.guard.keys.length]),
+    ...params.guard.keys.map((key: string) => Buffer.from(key, "hex"))
+  ];
+  const gasPayer = params.gasPayer === undefined ? [] :
+    [params.gasPayer.account, params.gasPayer.module, params.gasPayer.limit, params.gasPayer.price].map(str);
+  let transfer: Buffer[];
+  if (params.continuation === undefined) {
+    transfer = [params.recipient, params.recipientChainId, params.network, params.amount,
+                params.namespace, params.module].map(str);
+  } else {
+    const proofLength = Buffer.alloc(4);
+    proofLength.writeUInt32LE(params.continuation.proof.length);
+    transfer = [
+      str(params.network),
+      str(params.continuation.pactId),
+      str(params.continuation.step),
+      Buffer.from([params.continuation.rollback ? 1 : 0]),
+      proofLength,
+      Buffer.from(params.continuation.proof, "utf-8")
+    ];
+  }
+  return Buffer.concat([
+    bip32KeyPayload(params.path),
+    Buffer.from([params.txType]),
+    ...sender,
+    ...recipientKind,
+    ...guard,
+    ...gasPayer,
+    ...transfer,
+    ...[params.gasPrice, params.gasLimit, params.creationTime, params.chainId, params.nonce,
+        params.ttl].map(str)
+  ]);
+}
+
+function checkMakeTransferTx(params: any, txn: string, prompts: any[]) {
+  return async () => {
+    await sendCommandAndAccept(
+      async (client : Kda) => {
+        const { publicKey } = await client.getPublicKey(params.path);
+        await Axios.delete(BASE_URL + "/events");
+        let rv : Buffer = await (client as any).sendChunks(0x00, 0x10, 0x00, 0x00, makeTransferTxPayload(params));
+        const hash = blake2b(32).update(Buffer.from(txn, "utf-8")).digest();
+        // The length and hash of the command come first, and a further chunk asks for the signature
+        if ((params.txType & 0x10) != 0) {
+          expect(rv.length).to.equal(36);
+          expect(rv.readUInt32LE(0)).to.equal(Buffer.byteLength(txn, "utf-8"));
+          expect(rv.slice(4).toString("hex")).to.equal(Buffer.from(hash).toString("hex"));
+          rv = await (client as any).sendChunks(0x00, 0x10, 0x00, 0x00, Buffer.from([0x01]));
+        }
+        expect(rv.length).to.equal(96);
+        expect(rv.slice(64, 96).toString("hex")).to.equal(Buffer.from(publicKey).toString("hex"));
+        const pass = nacl.crypto_sign_verify_detached(rv.slice(0, 64), hash, publicKey);
+        expect(pass).to.equal(true);
+      }, prompts);
+  }
+}
+
+function checkMakeTransferTxFail(params: any) {
+  return async () => {
+    await sendCommandExpectFail(
+      async (client : Kda) => {
+        await (client as any).sendChunks(0x00, 0x10, 0x00, 0x00, makeTransferTxPayload(params));
+      });
+  }
+}
+
+const NAMED_SENDER_PARAMS = {
+  path: "44'/626'/0'/0/0",
+  txType: 0x80,
+  sender: "alice",
+  recipient: '83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790',
+  recipientChainId: "0",
+  network: "testnet04",
+  amount: "1.23",
+  namespace: "",
+  module: "",
+  gasPrice: "1.0e-6",
+  gasLimit: "2300",
+  creationTime: "1665647810",
+  chainId: "0",
+  nonce: "2022-10-13 07:56:50.893257 UTC",
+  ttl: "600"
+};
+
+// Long enough to span several chunks
+const CONTINUATION_PROOF = "CzBVep_E6Q4zWH2ix-wRNluApcrvFDleg6jN8hc8YYar0PUaP2SJrtP4HUJnjLHW-yBFao-02f4jSG2St9wBJktwlbrfBClOc5i94gcsUXabwOUKL1R5nsPoDTJXfKHG6xA1Wn-kye4TOF2Cp8zxFjtgharP9Bk-Y4it0vccQWaLsNX6H0RpjrPY_SJHbJG22wAlSm-Uud4DKE1yl7zhBitQdZq_5AkuU3idwucMMVZ7oMXqDzRZfqPI7RI3XIGmy_AVOl-Eqc7zGD1ih6zR9htAZYqv1PkeQ2iNstf8IUZrkLXa_yRJbpO43QInTHGWu-AFKk90mb7jCC1Sd5zB5gswVXqfxOkOM1h9osfsETZbgKXK7xQ5XoOozfIXPGGGq9D1Gj9kia7T-B1CZ4yx1vsgRWqPtNn-I0htkrfcASZLcJW63wQpTnOYveIHLFF2m8DlCi9UeZ7D6A0yV3yhxusQNVp_pMnuEzhdgqfM8RY7YIWqz_QZPmOIrdL3HEFmi7DV-h9EaY6z2P0iR2yRttsAJUpvlLneAyhNcpe84QYrUHWav-QJLlN4ncLnDDFWe6DF6g80WX6jyO0SN1yBpsvwFTpfhKnO8xg9Yoes0fYbQGWKr9T5HkNojbLX_CFGa5C12v8kSW6TuN0CJ0xxlrvgBSpPdJm-4wgtUnecweYLMFV6n8TpDjNYfaLH7BE2W4Clyu8UOV6DqM3yFzxhhqvQ9Ro_ZImu0_gdQmeMsdb7IEVqj7TZ_iNIbZK33AEmS3CVut8EKU5zmL3iByxRdpvA5QovVHme";
+
+const MULTISIG_GUARD = {
+  pred: "keys-2",
+  keys: [
+    "368820f80c324bbc7c2b0610688a7da43e39f91d118732671cd9c7500ff43cca",
+    "83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790"
+  ]
+};
+
 describe('Create Tx tests', function() {
   it("can build a transfer tx",
      checkSignTransferTxAPIs(
@@ -1202,7 +2437,8 @@ describe('Create Tx tests', function() {
          { "header": "To", "prompt": "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
          { "header": "Amount", "prompt": "KDA 1.23" },
          { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
-         { "header": "Gas Price (2/2)", "prompt": "KDA 1.0e-6" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
          {"text": "Sign Transaction?", "x": 19, "y": 11,},
          {"text": "Confirm", "x": 43, "y": 11,}
        ]
@@ -1229,7 +2465,8 @@ describe('Create Tx tests', function() {
          { "header": "To", "prompt": "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
          { "header": "Amount", "prompt": "KDA 23.67" },
          { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
-         { "header": "Gas Price (2/2)", "prompt": "KDA 1.0e-6" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
          {"text": "Sign Transaction?", "x": 19, "y": 11,},
          {"text": "Confirm", "x": 43, "y": 11,}
        ]
@@ -1258,7 +2495,8 @@ describe('Create Tx tests', function() {
          { "header": "To Chain", "prompt": "2" },
          { "header": "Amount", "prompt": "KDA 23.67" },
          { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
-         { "header": "Gas Price (2/2)", "prompt": "KDA 1.0e-6" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
          {"text": "Sign Transaction?", "x": 19, "y": 11,},
          {"text": "Confirm", "x": 43, "y": 11,}
        ]
@@ -1288,7 +2526,8 @@ describe('Create Tx tests', function() {
          { "header": "To", "prompt": "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
          { "header": "Amount", "prompt": "1.23" },
          { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
-         { "header": "Gas Price (2/2)", "prompt": "KDA 1.0e-6" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
          {"text": "Sign Transaction?", "x": 19, "y": 11,},
          {"text": "Confirm", "x": 43, "y": 11,}
        ]
@@ -1318,7 +2557,8 @@ describe('Create Tx tests', function() {
          { "header": "To", "prompt": "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
          { "header": "Amount", "prompt": "23.67" },
          { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
-         { "header": "Gas Price (2/2)", "prompt": "KDA 1.0e-6" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
          {"text": "Sign Transaction?", "x": 19, "y": 11,},
          {"text": "Confirm", "x": 43, "y": 11,}
        ]
@@ -1349,7 +2589,186 @@ describe('Create Tx tests', function() {
          { "header": "To Chain", "prompt": "2" },
          { "header": "Amount", "prompt": "23.67" },
          { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
-         { "header": "Gas Price (2/2)", "prompt": "KDA 1.0e-6" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
+         {"text": "Sign Transaction?", "x": 19, "y": 11,},
+         {"text": "Confirm", "x": 43, "y": 11,}
+       ]
+     ));
+  it("rejects a KDA amount with more than 12 decimal places",
+     checkSignTransferTxFail(
+       "signTransferTx",
+       {
+         path: "44'/626'/0'/0/0",
+         recipient: '83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790',
+         amount: "1.0000000000001",
+         network: "testnet04",
+         chainId: 0,
+         gasPrice: "1.0e-6",
+         gasLimit: "2300",
+         creationTime: 1665647810,
+         ttl: "600",
+         nonce: "2022-10-13 07:56:50.893257 UTC"
+       }));
+  it("rejects an amount without digits after the decimal point",
+     checkSignTransferTxFail(
+       "signTransferTx",
+       {
+         path: "44'/626'/0'/0/0",
+         recipient: '83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790',
+         amount: "1.",
+         network: "testnet04",
+         chainId: 0,
+         gasPrice: "1.0e-6",
+         gasLimit: "2300",
+         creationTime: 1665647810,
+         ttl: "600",
+         nonce: "2022-10-13 07:56:50.893257 UTC"
+       }));
+  it("can build a transfer tx from a named sender",
+     checkMakeTransferTx(
+       NAMED_SENDER_PARAMS,
+       "{\"networkId\":\"testnet04\",\"payload\":{\"exec\":{\"data\":{},\"code\":\"(coin.transfer \\\"alice\\\" \\\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\\" 1.23)\"}},\"signers\":[{\"pubKey\":\"9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"clist\":[{\"args\":[\"alice\",\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\",1.23],\"name\":\"coin.TRANSFER\"},{\"args\":[],\"name\":\"coin.GAS\"}]}],\"meta\":{\"creationTime\":1665647810,\"ttl\":600,\"gasLimit\":2300,\"chainId\":\"0\",\"gasPrice\":1.0e-6,\"sender\":\"alice\"},\"nonce\":\"2022-10-13 07:56:50.893257 UTC\"}",
+       [
+         { "header": "Transfer", "prompt": "KDA" },
+         { "header": "From", "prompt": "alice with key 9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995", "paginate": true },
+         { "header": "To", "prompt": "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Amount", "prompt": "KDA 1.23" },
+         { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
+         {"text": "Sign Transaction?", "x": 19, "y": 11,},
+         {"text": "Confirm", "x": 43, "y": 11,}
+       ]
+     ));
+  it("rejects a k: sender which is not the account of the key",
+     checkMakeTransferTxFail(
+       { ...NAMED_SENDER_PARAMS, sender: "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790" }));
+  it("rejects a gas price too long for the app with a field too long status", async () => {
+    await sendCommandAndAccept(async (client : Kda) => {
+      let statusCode = null;
+      try {
+        await (client as any).sendChunks(0x00, 0x10, 0x00, 0x00,
+          makeTransferTxPayload({ ...NAMED_SENDER_PARAMS, gasPrice: "0.0000000000000000001" }));
+      } catch (e : any) {
+        statusCode = e.statusCode;
+      }
+      expect(statusCode).to.equal(0xB003);
+    }, []);
+  });
+  it("can build a transfer tx from a sender with quotes and backslashes in its name",
+     checkMakeTransferTx(
+       { ...NAMED_SENDER_PARAMS, sender: "al\"i\\ce" },
+       "{\"networkId\":\"testnet04\",\"payload\":{\"exec\":{\"data\":{},\"code\":\"(coin.transfer \\\"al\\\\\\\"i\\\\\\\\ce\\\" \\\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\\" 1.23)\"}},\"signers\":[{\"pubKey\":\"9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"clist\":[{\"args\":[\"al\\\"i\\\\ce\",\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\",1.23],\"name\":\"coin.TRANSFER\"},{\"args\":[],\"name\":\"coin.GAS\"}]}],\"meta\":{\"creationTime\":1665647810,\"ttl\":600,\"gasLimit\":2300,\"chainId\":\"0\",\"gasPrice\":1.0e-6,\"sender\":\"al\\\"i\\\\ce\"},\"nonce\":\"2022-10-13 07:56:50.893257 UTC\"}",
+       [
+         { "header": "Transfer", "prompt": "KDA" },
+         { "header": "From", "prompt": "al\"i\\ce with key 9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995", "paginate": true },
+         { "header": "To", "prompt": "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Amount", "prompt": "KDA 1.23" },
+         { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
+         {"text": "Sign Transaction?", "x": 19, "y": 11,},
+         {"text": "Confirm", "x": 43, "y": 11,}
+       ]
+     ));
+  it("can build a transfer tx to a w: account",
+     checkMakeTransferTx(
+       { ...NAMED_SENDER_PARAMS, txType: 0x40, sender: undefined, recipientKind: 1,
+         recipient: "w:6rmNLomerCA3oH143sWGmVJQ2UGycclXGcgYCkGAZSA:keys-2", guard: MULTISIG_GUARD },
+       "{\"networkId\":\"testnet04\",\"payload\":{\"exec\":{\"data\":{},\"code\":\"(coin.transfer \\\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\\\" \\\"w:6rmNLomerCA3oH143sWGmVJQ2UGycclXGcgYCkGAZSA:keys-2\\\" 1.23)\"}},\"signers\":[{\"pubKey\":\"9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"clist\":[{\"args\":[\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"w:6rmNLomerCA3oH143sWGmVJQ2UGycclXGcgYCkGAZSA:keys-2\",1.23],\"name\":\"coin.TRANSFER\"},{\"args\":[],\"name\":\"coin.GAS\"}]}],\"meta\":{\"creationTime\":1665647810,\"ttl\":600,\"gasLimit\":2300,\"chainId\":\"0\",\"gasPrice\":1.0e-6,\"sender\":\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\"},\"nonce\":\"2022-10-13 07:56:50.893257 UTC\"}",
+       [
+         { "header": "Transfer", "prompt": "KDA" },
+         { "header": "From", "prompt": "k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995", "paginate": true },
+         { "header": "To", "prompt": "w:6rmNLomerCA3oH143sWGmVJQ2UGycclXGcgYCkGAZSA:keys-2", "paginate": true },
+         { "header": "Keyset", "prompt": "keys-2 [368820f80c324bbc7c2b0610688a7da43e39f91d118732671cd9c7500ff43cca, 83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790]", "paginate": true },
+         { "header": "Amount", "prompt": "KDA 1.23" },
+         { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
+         {"text": "Sign Transaction?", "x": 19, "y": 11,},
+         {"text": "Confirm", "x": 43, "y": 11,}
+       ]
+     ));
+  it("can build a transfer-create tx to a named account with a multi-key guard",
+     checkMakeTransferTx(
+       { ...NAMED_SENDER_PARAMS, txType: 0x41, sender: undefined, recipientKind: 2,
+         recipient: "bob-multisig", guard: MULTISIG_GUARD },
+       "{\"networkId\":\"testnet04\",\"payload\":{\"exec\":{\"data\":{\"ks\":{\"pred\":\"keys-2\",\"keys\":[\"368820f80c324bbc7c2b0610688a7da43e39f91d118732671cd9c7500ff43cca\",\"83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\"]}},\"code\":\"(coin.transfer-create \\\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\\\" \\\"bob-multisig\\\" (read-keyset \\\"ks\\\") 1.23)\"}},\"signers\":[{\"pubKey\":\"9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"clist\":[{\"args\":[\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"bob-multisig\",1.23],\"name\":\"coin.TRANSFER\"},{\"args\":[],\"name\":\"coin.GAS\"}]}],\"meta\":{\"creationTime\":1665647810,\"ttl\":600,\"gasLimit\":2300,\"chainId\":\"0\",\"gasPrice\":1.0e-6,\"sender\":\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\"},\"nonce\":\"2022-10-13 07:56:50.893257 UTC\"}",
+       [
+         { "header": "Transfer", "prompt": "KDA" },
+         { "header": "From", "prompt": "k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995", "paginate": true },
+         { "header": "To", "prompt": "bob-multisig", "paginate": true },
+         { "header": "Keyset", "prompt": "keys-2 [368820f80c324bbc7c2b0610688a7da43e39f91d118732671cd9c7500ff43cca, 83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790]", "paginate": true },
+         { "header": "Amount", "prompt": "KDA 1.23" },
+         { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
+         {"text": "Sign Transaction?", "x": 19, "y": 11,},
+         {"text": "Confirm", "x": 43, "y": 11,}
+       ]
+     ));
+  it("rejects a w: account which does not match its guard",
+     checkMakeTransferTxFail(
+       { ...NAMED_SENDER_PARAMS, txType: 0x40, sender: undefined, recipientKind: 1,
+         recipient: "w:6rmNLomerCA3oH143sWGmVJQ2UGycclXGcgYCkGAZSA:keys-2", guard: { ...MULTISIG_GUARD, pred: "keys-any" } }));
+  it("can build a transfer tx with gas paid by a gas station",
+     checkMakeTransferTx(
+       { ...NAMED_SENDER_PARAMS, txType: 0x20, sender: undefined,
+         gasPayer: { account: "my-gas-station", module: "free.my-gas-station", limit: "2300", price: "1.0e-6" } },
+       "{\"networkId\":\"testnet04\",\"payload\":{\"exec\":{\"data\":{},\"code\":\"(coin.transfer \\\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\\\" \\\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\\" 1.23)\"}},\"signers\":[{\"pubKey\":\"9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"clist\":[{\"args\":[\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\",1.23],\"name\":\"coin.TRANSFER\"},{\"args\":[\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",{\"int\":2300},1.0e-6],\"name\":\"free.my-gas-station.GAS_PAYER\"}]}],\"meta\":{\"creationTime\":1665647810,\"ttl\":600,\"gasLimit\":2300,\"chainId\":\"0\",\"gasPrice\":1.0e-6,\"sender\":\"my-gas-station\"},\"nonce\":\"2022-10-13 07:56:50.893257 UTC\"}",
+       [
+         { "header": "Transfer", "prompt": "KDA" },
+         { "header": "From", "prompt": "k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995", "paginate": true },
+         { "header": "To", "prompt": "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Amount", "prompt": "KDA 1.23" },
+         { "header": "Gas paid by", "prompt": "my-gas-station with free.my-gas-station.GAS_PAYER", "paginate": true },
+         { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
+         {"text": "Sign Transaction?", "x": 19, "y": 11,},
+         {"text": "Confirm", "x": 43, "y": 11,}
+       ]
+     ));
+  it("rejects a k: gas payer",
+     checkMakeTransferTxFail(
+       { ...NAMED_SENDER_PARAMS, txType: 0x20, sender: undefined,
+         gasPayer: { account: "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", module: "coin", limit: "2300", price: "1.0e-6" } }));
+  it("can build the continuation of a cross-chain transfer",
+     checkMakeTransferTx(
+       { ...NAMED_SENDER_PARAMS, txType: 3, sender: undefined, chainId: "2",
+         continuation: { pactId: "bMT6Rg8bUiXrvZqBNJNmd9kUMAMY2hiAAVnRpzeulRA", step: "1", rollback: false, proof: CONTINUATION_PROOF } },
+       "{\"networkId\":\"testnet04\",\"payload\":{\"cont\":{\"pactId\":\"bMT6Rg8bUiXrvZqBNJNmd9kUMAMY2hiAAVnRpzeulRA\",\"step\":1,\"rollback\":false,\"data\":{},\"proof\":\"" + CONTINUATION_PROOF + "\"}},\"signers\":[{\"pubKey\":\"9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"clist\":[{\"args\":[],\"name\":\"coin.GAS\"}]}],\"meta\":{\"creationTime\":1665647810,\"ttl\":600,\"gasLimit\":2300,\"chainId\":\"2\",\"gasPrice\":1.0e-6,\"sender\":\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\"},\"nonce\":\"2022-10-13 07:56:50.893257 UTC\"}",
+       [
+         { "header": "Continuing Pact", "prompt": "bMT6Rg8bUiXrvZqBNJNmd9kUMAMY2hiAAVnRpzeulRA", "paginate": true },
+         { "header": "Step", "prompt": "1" },
+         { "header": "Rollback", "prompt": "No" },
+         { "header": "SPV Proof", "prompt": "Attached" },
+         { "header": "Target Chain", "prompt": "2" },
+         { "header": "Gas paid by", "prompt": "k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995", "paginate": true },
+         { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
+         {"text": "Sign Transaction?", "x": 19, "y": 11,},
+         {"text": "Confirm", "x": 43, "y": 11,}
+       ]
+     ));
+  it("rejects a continuation proof which is not base64url",
+     checkMakeTransferTxFail(
+       { ...NAMED_SENDER_PARAMS, txType: 3, sender: undefined, chainId: "2",
+         continuation: { pactId: "bMT6Rg8bUiXrvZqBNJNmd9kUMAMY2hiAAVnRpzeulRA", step: "1", rollback: false, proof: "not\"base64" } }));
+  it("returns the length and hash of the command when asked for",
+     checkMakeTransferTx(
+       { ...NAMED_SENDER_PARAMS, txType: 0x10, sender: undefined },
+       "{\"networkId\":\"testnet04\",\"payload\":{\"exec\":{\"data\":{},\"code\":\"(coin.transfer \\\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\\\" \\\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\\\" 1.23)\"}},\"signers\":[{\"pubKey\":\"9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"clist\":[{\"args\":[\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\",\"k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790\",1.23],\"name\":\"coin.TRANSFER\"},{\"args\":[],\"name\":\"coin.GAS\"}]}],\"meta\":{\"creationTime\":1665647810,\"ttl\":600,\"gasLimit\":2300,\"chainId\":\"0\",\"gasPrice\":1.0e-6,\"sender\":\"k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995\"},\"nonce\":\"2022-10-13 07:56:50.893257 UTC\"}",
+       [
+         { "header": "Transfer", "prompt": "KDA" },
+         { "header": "From", "prompt": "k:9ed54a1020ebbbf8bbe425346498434edd79e4cd36fe874ea58853e78eab4995", "paginate": true },
+         { "header": "To", "prompt": "k:83934c0f9b005f378ba3520f9dea952fb0a90e5aa36f1b5ff837d9b30c471790", "paginate": true },
+         { "header": "Amount", "prompt": "KDA 1.23" },
+         { "header": "Gas Limit (1/2)", "prompt": "2300 Max" },
+         { "header": "Gas Price (2/2)", "prompt": "KDA 0.000001" },
+         { "header": "Max Fee", "prompt": "KDA 0.0023" },
          {"text": "Sign Transaction?", "x": 19, "y": 11,},
          {"text": "Confirm", "x": 43, "y": 11,}
        ]